@@ -0,0 +1,82 @@
+//! 原子、崩溃安全的文件写入，外加备份轮转。
+//!
+//! `std::fs::write` 会先截断目标再写入，中途崩溃或磁盘写满会留下损坏/空文件，毁掉
+//! 用户的收藏导出或设置。这里改为：写到同目录的临时文件（同一文件系统，`rename`
+//! 原子），`flush` + `sync_all` 落盘后再 `rename` 覆盖目标，失败则原文件完好无损。
+//! 覆盖前把已有文件轮转为 `name.bak.N`，保留最近 N 份，便于回滚一次坏的导入/导出。
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// 默认保留的备份份数。
+pub const DEFAULT_BACKUPS: usize = 3;
+
+/// 原子写入 `path`，覆盖前把已有文件轮转出最多 `backups` 份历史备份。
+///
+/// 返回本次轮转保存的上一版路径（`name.bak.1`）；目标原先不存在时为 `None`，供调用方
+/// 向用户提示“旧版本已保存到 …”。
+pub fn write(path: &Path, contents: &[u8], backups: usize) -> Result<Option<PathBuf>, String> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+
+    let backup = if path.exists() {
+        rotate_backups(path, backups)?
+    } else {
+        None
+    };
+
+    // 临时文件与目标同目录，确保 rename 在同一文件系统上原子完成。
+    let tmp = temp_path(path);
+    {
+        let mut file = std::fs::File::create(&tmp).map_err(|e| e.to_string())?;
+        file.write_all(contents).map_err(|e| e.to_string())?;
+        file.flush().map_err(|e| e.to_string())?;
+        file.sync_all().map_err(|e| e.to_string())?;
+    }
+    std::fs::rename(&tmp, path).map_err(|e| {
+        let _ = std::fs::remove_file(&tmp);
+        e.to_string()
+    })?;
+
+    Ok(backup)
+}
+
+/// 用默认份数原子写入。
+pub fn write_default(path: &Path, contents: &[u8]) -> Result<Option<PathBuf>, String> {
+    write(path, contents, DEFAULT_BACKUPS)
+}
+
+/// 覆盖前把现有文件整体后移一位：丢弃最旧的 `bak.N`，`bak.k` -> `bak.k+1`，
+/// 最后把当前文件移到 `bak.1`。`backups` 为 0 时直接删除当前文件、不留备份。
+fn rotate_backups(path: &Path, backups: usize) -> Result<Option<PathBuf>, String> {
+    if backups == 0 {
+        std::fs::remove_file(path).map_err(|e| e.to_string())?;
+        return Ok(None);
+    }
+
+    let oldest = backup_path(path, backups);
+    let _ = std::fs::remove_file(&oldest);
+    for n in (1..backups).rev() {
+        let from = backup_path(path, n);
+        if from.exists() {
+            std::fs::rename(&from, backup_path(path, n + 1)).map_err(|e| e.to_string())?;
+        }
+    }
+
+    let newest = backup_path(path, 1);
+    std::fs::rename(path, &newest).map_err(|e| e.to_string())?;
+    Ok(Some(newest))
+}
+
+fn backup_path(path: &Path, n: usize) -> PathBuf {
+    let mut name = path.file_name().map(|s| s.to_os_string()).unwrap_or_default();
+    name.push(format!(".bak.{}", n));
+    path.with_file_name(name)
+}
+
+fn temp_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().map(|s| s.to_os_string()).unwrap_or_default();
+    name.push(".tmp");
+    path.with_file_name(name)
+}
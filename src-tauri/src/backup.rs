@@ -0,0 +1,277 @@
+use std::path::{Path, PathBuf};
+
+use base64::Engine;
+
+use crate::models::ClipboardRecord;
+
+/// 历史导出/导入支持的格式。
+#[derive(Clone, Copy, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Json,
+    Csv,
+}
+
+fn base64_engine() -> base64::engine::GeneralPurpose {
+    base64::engine::general_purpose::STANDARD
+}
+
+/// 在线备份到指定文件。
+#[tauri::command]
+pub fn backup_database(path: String) -> Result<(), String> {
+    crate::database::backup_to(Path::new(path.trim()))
+}
+
+/// 从备份文件还原（校验 schema 后换入）。
+#[tauri::command]
+pub fn restore_database(path: String) -> Result<(), String> {
+    crate::database::restore_from(Path::new(path.trim()))
+}
+
+/// 把一个字段按 CSV 规则转义（含逗号/引号/换行时加引号并翻倍内部引号）。
+fn csv_escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn records_to_csv(records: &[ClipboardRecord]) -> String {
+    let mut out = String::from("content_type,content,source_app,is_favorite,is_pinned,created_at\n");
+    for record in records {
+        let content = if record.content_type == "image" {
+            record
+                .image_data
+                .as_ref()
+                .map(|bytes| base64_engine().encode(bytes))
+                .unwrap_or_default()
+        } else {
+            record.content.clone()
+        };
+
+        out.push_str(&csv_escape(&record.content_type));
+        out.push(',');
+        out.push_str(&csv_escape(&content));
+        out.push(',');
+        out.push_str(&csv_escape(&record.source_app));
+        out.push(',');
+        out.push_str(if record.is_favorite { "1" } else { "0" });
+        out.push(',');
+        out.push_str(if record.is_pinned { "1" } else { "0" });
+        out.push(',');
+        out.push_str(&csv_escape(&record.created_at));
+        out.push('\n');
+    }
+    out
+}
+
+/// 导出全部历史记录到 `path`，返回实际写入的文件路径。
+#[tauri::command]
+pub fn export_history(path: String, format: ExportFormat) -> Result<String, String> {
+    let records = crate::database::get_all_records().map_err(|e| e.to_string())?;
+
+    let mut output = PathBuf::from(path.trim());
+    let payload = match format {
+        ExportFormat::Json => {
+            if output.extension().is_none() {
+                output.set_extension("json");
+            }
+            serde_json::to_string_pretty(&records).map_err(|e| e.to_string())?
+        }
+        ExportFormat::Csv => {
+            if output.extension().is_none() {
+                output.set_extension("csv");
+            }
+            records_to_csv(&records)
+        }
+    };
+
+    if let Some(parent) = output.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    std::fs::write(&output, payload).map_err(|e| e.to_string())?;
+    Ok(output.to_string_lossy().to_string())
+}
+
+/// 把 CSV 文本切成逻辑记录：引号内的换行视为字段内容，不作为记录分隔。
+///
+/// 逐字符扫描并维护 `in_quotes`（翻倍引号表示字面量引号，不切换状态），仅在引号
+/// 外遇到换行时才结束一条记录，从而让 `records_to_csv` 写出的多行字段在导入时完整
+/// 还原，而不是被 `str::lines` 拆成多段后因字段不足被丢弃。
+fn split_csv_records(data: &str) -> Vec<String> {
+    let mut records = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut chars = data.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '"' => {
+                current.push(ch);
+                if in_quotes && chars.peek() == Some(&'"') {
+                    // 翻倍引号是字面量，整对保留且不切换引号状态。
+                    current.push('"');
+                    chars.next();
+                } else {
+                    in_quotes = !in_quotes;
+                }
+            }
+            '\n' if !in_quotes => {
+                // 吞掉 CRLF 行尾多出的 '\r'，引号内的 '\r' 属字段内容不受影响。
+                if current.ends_with('\r') {
+                    current.pop();
+                }
+                records.push(std::mem::take(&mut current));
+            }
+            _ => current.push(ch),
+        }
+    }
+    if current.ends_with('\r') {
+        current.pop();
+    }
+    if !current.is_empty() {
+        records.push(current);
+    }
+    records
+}
+
+/// 解析一行 CSV（支持引号包裹与翻倍转义）。
+fn parse_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '"' if in_quotes => {
+                if chars.peek() == Some(&'"') {
+                    field.push('"');
+                    chars.next();
+                } else {
+                    in_quotes = false;
+                }
+            }
+            '"' => in_quotes = true,
+            ',' if !in_quotes => {
+                fields.push(std::mem::take(&mut field));
+            }
+            _ => field.push(ch),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+fn insert_imported_record(
+    content_type: &str,
+    content: &str,
+    source_app: &str,
+    is_favorite: bool,
+    is_pinned: bool,
+    created_at: &str,
+    image_data: Option<Vec<u8>>,
+) -> Result<(), String> {
+    let record = ClipboardRecord {
+        id: 0,
+        content_type: content_type.to_string(),
+        content: content.to_string(),
+        html: None,
+        bundle: None,
+        image_data,
+        is_favorite,
+        is_pinned,
+        source_app: if source_app.trim().is_empty() {
+            "Import".to_string()
+        } else {
+            source_app.to_string()
+        },
+        created_at: if created_at.trim().is_empty() {
+            chrono::Local::now().to_rfc3339()
+        } else {
+            created_at.to_string()
+        },
+    };
+    // add_record 内置的文本去重/合并逻辑会处理重复条目。
+    crate::database::add_record(record)
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+fn import_csv(data: &str) -> Result<i32, String> {
+    let mut imported = 0;
+    for (idx, line) in split_csv_records(data).iter().enumerate() {
+        if idx == 0 || line.trim().is_empty() {
+            continue; // 跳过表头与空行
+        }
+        let fields = parse_csv_line(line);
+        if fields.len() < 6 {
+            continue;
+        }
+
+        let content_type = fields[0].trim();
+        let raw_content = &fields[1];
+        let source_app = fields[2].trim();
+        let is_favorite = fields[3].trim() == "1";
+        let is_pinned = fields[4].trim() == "1";
+        let created_at = fields[5].trim();
+
+        let (content, image_data) = if content_type == "image" {
+            match base64_engine().decode(raw_content.trim()) {
+                Ok(bytes) => (String::new(), Some(bytes)),
+                Err(_) => continue, // 跳过损坏的图片行
+            }
+        } else {
+            (raw_content.clone(), None)
+        };
+
+        if content.trim().is_empty() && image_data.is_none() {
+            continue;
+        }
+
+        insert_imported_record(
+            content_type,
+            &content,
+            source_app,
+            is_favorite,
+            is_pinned,
+            created_at,
+            image_data,
+        )?;
+        imported += 1;
+    }
+    Ok(imported)
+}
+
+fn import_json(data: &str) -> Result<i32, String> {
+    let records: Vec<ClipboardRecord> =
+        serde_json::from_str(data).map_err(|e| format!("invalid json: {}", e))?;
+    let mut imported = 0;
+    for record in records {
+        if record.content.trim().is_empty() && record.image_data.is_none() {
+            continue;
+        }
+        insert_imported_record(
+            &record.content_type,
+            &record.content,
+            &record.source_app,
+            record.is_favorite,
+            record.is_pinned,
+            &record.created_at,
+            record.image_data,
+        )?;
+        imported += 1;
+    }
+    Ok(imported)
+}
+
+/// 从 `path` 导入历史记录，返回成功导入的条数。文本记录经由 `add_record` 去重。
+#[tauri::command]
+pub fn import_history(path: String, format: ExportFormat) -> Result<i32, String> {
+    let data = std::fs::read_to_string(path.trim()).map_err(|e| e.to_string())?;
+    match format {
+        ExportFormat::Json => import_json(&data),
+        ExportFormat::Csv => import_csv(&data),
+    }
+}
@@ -3,8 +3,8 @@ use arboard::{Clipboard, ImageData};
 use clipboard_master::{CallbackResult, ClipboardHandler, Master};
 use enigo::{Enigo, Key, KeyboardControllable};
 use image::{codecs::png::PngEncoder, ColorType, ImageEncoder, ImageFormat};
+use serde::Deserialize;
 use std::borrow::Cow;
-use std::hash::{Hash, Hasher};
 #[cfg(target_os = "windows")]
 use std::ptr;
 #[cfg(target_os = "windows")]
@@ -17,6 +17,15 @@ use tauri::{AppHandle, Emitter, Manager};
 #[cfg(target_os = "windows")]
 use windows_sys::Win32::Foundation::CloseHandle;
 #[cfg(target_os = "windows")]
+use windows_sys::Win32::System::DataExchange::{
+    CloseClipboard, EmptyClipboard, EnumClipboardFormats, GetClipboardData,
+    GetClipboardFormatNameW, OpenClipboard, RegisterClipboardFormatW, SetClipboardData,
+};
+#[cfg(target_os = "windows")]
+use windows_sys::Win32::System::Memory::{
+    GlobalAlloc, GlobalLock, GlobalSize, GlobalUnlock, GMEM_MOVEABLE,
+};
+#[cfg(target_os = "windows")]
 use windows_sys::Win32::System::Threading::{
     AttachThreadInput, GetCurrentThreadId, OpenProcess, QueryFullProcessImageNameW,
     PROCESS_NAME_FORMAT, PROCESS_QUERY_LIMITED_INFORMATION,
@@ -26,7 +35,7 @@ use windows_sys::Win32::UI::WindowsAndMessaging::{
     GetForegroundWindow, GetWindowThreadProcessId, SetForegroundWindow,
 };
 
-use crate::models::ClipboardRecord;
+use crate::models::{ClipboardFormatBlob, ClipboardRecord};
 
 static MONITORING: AtomicBool = AtomicBool::new(false);
 static MONITOR_SESSION_ID: AtomicU64 = AtomicU64::new(0);
@@ -36,13 +45,16 @@ static IGNORE_NEXT_CHANGE: AtomicBool = AtomicBool::new(false);
 static TARGET_HWND: AtomicPtr<std::ffi::c_void> = AtomicPtr::new(ptr::null_mut());
 static LAST_IMAGE_RECORD_MS: AtomicU64 = AtomicU64::new(0);
 static PASTE_IN_PROGRESS: AtomicBool = AtomicBool::new(false);
-const ENABLE_IMAGE_RECORDING: bool = false;
+/// 是否记录图片剪贴板，由 [`Settings::enable_image_recording`] 在运行时驱动。
+static IMAGE_RECORDING_ENABLED: AtomicBool = AtomicBool::new(false);
 
 const MAX_IMAGE_BYTES: usize = 48 * 1024 * 1024; // raw RGBA cap before processing
 const MAX_IMAGE_PIXELS: usize = 2_600_000; // approx <= 1920x1350
 const MAX_IMAGE_DIMENSION: usize = 2200;
 const MAX_ENCODED_IMAGE_BYTES: usize = 6 * 1024 * 1024; // PNG blob cap
 const MIN_IMAGE_RECORD_INTERVAL_MS: u64 = 1200;
+/// aHash 指纹间的汉明距离不超过该阈值即视为同一张图片，抑制近乎相同的重复复制。
+const IMAGE_HASH_MATCH_DISTANCE: u32 = 5;
 const PASTE_SETTLE_MS_TEXT: u64 = 5;
 const PASTE_SETTLE_MS_IMAGE: u64 = 20;
 const PASTE_KEY_STEP_MS: u64 = 2;
@@ -142,6 +154,8 @@ fn build_image_record(width: usize, height: usize, rgba: &[u8]) -> Result<Clipbo
         } else {
             format!("图片 {}x{}", width, height)
         },
+        html: None,
+        bundle: None,
         image_data: Some(png_bytes),
         is_favorite: false,
         is_pinned: false,
@@ -156,6 +170,8 @@ fn build_text_record(text: String) -> ClipboardRecord {
         id: 0,
         content_type: content_type.to_string(),
         content: text,
+        html: None,
+        bundle: None,
         image_data: None,
         is_favorite: false,
         is_pinned: false,
@@ -164,24 +180,155 @@ fn build_text_record(text: String) -> ClipboardRecord {
     }
 }
 
-fn image_signature(width: usize, height: usize, rgba: &[u8]) -> String {
-    let mut hasher = std::collections::hash_map::DefaultHasher::new();
-    width.hash(&mut hasher);
-    height.hash(&mut hasher);
-    rgba.len().hash(&mut hasher);
+/// 用富文本 HTML 正文及其纯文本影子构造一条 `html` 记录。
+///
+/// `content` 存纯文本影子（供列表展示、搜索、去重），`html` 存渲染用的 HTML；
+/// 粘贴时两者一并写回剪贴板，让目标应用自行挑选偏好的表示。
+fn build_html_record(html: String, shadow: String) -> ClipboardRecord {
+    ClipboardRecord {
+        id: 0,
+        content_type: "html".to_string(),
+        content: shadow,
+        html: Some(html),
+        bundle: None,
+        image_data: None,
+        is_favorite: false,
+        is_pinned: false,
+        source_app: get_source_app(),
+        created_at: chrono::Local::now().to_rfc3339(),
+    }
+}
+
+/// 去除 HTML 标签得到一份粗略的纯文本影子，仅在系统未同时提供纯文本时兜底。
+///
+/// 跳过 `<script>`/`<style>` 块的正文，并解码最常见的几个实体，避免把 CSS/脚本
+/// 或 `&amp;` 之类的转义原样写进列表、FTS 与去重键。
+fn html_to_plain_shadow(html: &str) -> String {
+    let mut out = String::with_capacity(html.len());
+    let mut in_tag = false;
+    let mut skip_to: Option<&str> = None;
+    let rest = html;
+    let mut idx = 0usize;
+    let lower = html.to_ascii_lowercase();
+
+    while idx < rest.len() {
+        // 处于 <script>/<style> 块内时，直接跳到其结束标签之后。
+        if let Some(close) = skip_to {
+            if let Some(pos) = lower[idx..].find(close) {
+                idx += pos + close.len();
+                skip_to = None;
+                in_tag = false;
+                continue;
+            } else {
+                break;
+            }
+        }
+
+        let ch = rest[idx..].chars().next().unwrap();
+        let ch_len = ch.len_utf8();
+        match ch {
+            '<' => {
+                in_tag = true;
+                if lower[idx..].starts_with("<script") {
+                    skip_to = Some("</script>");
+                } else if lower[idx..].starts_with("<style") {
+                    skip_to = Some("</style>");
+                }
+            }
+            '>' => in_tag = false,
+            _ if !in_tag => out.push(ch),
+            _ => {}
+        }
+        idx += ch_len;
+    }
+
+    let decoded = out
+        .replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&");
+    decoded.split_whitespace().collect::<Vec<_>>().join(" ")
+}
 
-    // 只采样部分字节，避免每次对超大图片做全量哈希
-    for b in rgba.iter().take(4096) {
-        b.hash(&mut hasher);
+/// 计算图片的 64 位 average-hash（aHash）指纹。
+///
+/// 先用 [`downscale_rgba_nearest`] 缩放到 8×8，把每个像素折算成亮度
+/// （`0.299R + 0.587G + 0.114B`），取 64 个亮度的均值，再令第 i 位在第 i 个像素
+/// 亮度不低于均值时置 1。相较逐字节采样，它对无意义的字节抖动稳健，又能区分
+/// 构图不同的截图。
+fn image_ahash(width: usize, height: usize, rgba: &[u8]) -> u64 {
+    const SIDE: usize = 8;
+    if width == 0 || height == 0 || rgba.len() < width * height * 4 {
+        return 0;
     }
-    for b in rgba.iter().rev().take(4096) {
-        b.hash(&mut hasher);
+
+    let small = downscale_rgba_nearest(width, height, rgba, SIDE, SIDE);
+    let mut luminance = [0f64; SIDE * SIDE];
+    for (i, lum) in luminance.iter_mut().enumerate() {
+        let p = i * 4;
+        *lum = 0.299 * small[p] as f64 + 0.587 * small[p + 1] as f64 + 0.114 * small[p + 2] as f64;
+    }
+
+    let mean = luminance.iter().sum::<f64>() / luminance.len() as f64;
+    let mut hash = 0u64;
+    for (i, &lum) in luminance.iter().enumerate() {
+        if lum >= mean {
+            hash |= 1u64 << i;
+        }
     }
+    hash
+}
+
+/// 指纹连同宽高前缀，确保尺寸不同的图片绝不会被判为同一张。
+fn image_signature(width: usize, height: usize, rgba: &[u8]) -> String {
+    format!("image:{}:{}:{}", width, height, image_ahash(width, height, rgba))
+}
+
+/// 还原 [`image_signature`] 的 `image:宽:高:指纹` 形式；非图片签名返回 `None`。
+fn parse_image_signature(signature: &str) -> Option<(usize, usize, u64)> {
+    let rest = signature.strip_prefix("image:")?;
+    let mut parts = rest.splitn(3, ':');
+    let width = parts.next()?.parse().ok()?;
+    let height = parts.next()?.parse().ok()?;
+    let hash = parts.next()?.parse().ok()?;
+    Some((width, height, hash))
+}
+
+/// 两个 aHash 指纹的汉明距离（异或后的置位数）。
+fn image_hash_distance(a: u64, b: u64) -> u32 {
+    (a ^ b).count_ones()
+}
+
+/// 读取运行时的图片记录开关。
+fn image_recording_enabled() -> bool {
+    IMAGE_RECORDING_ENABLED.load(Ordering::SeqCst)
+}
 
-    format!("image:{}:{}:{}", width, height, hasher.finish())
+/// 按 [`Settings::enable_image_recording`] 更新图片记录开关。
+pub fn set_image_recording_enabled(enabled: bool) {
+    IMAGE_RECORDING_ENABLED.store(enabled, Ordering::SeqCst);
 }
 
 
+/// 标记「忽略下一次剪贴板变化」。
+///
+/// 复制、粘贴等由本程序自行合成的剪贴板写入同样会触发监听器；在执行这类写入前
+/// 调用本函数，可让监听器跳过紧随其后的那一次变化，避免把自触发的内容再次记录。
+#[tauri::command]
+pub fn ignore_next_change() {
+    IGNORE_NEXT_CHANGE.store(true, Ordering::SeqCst);
+}
+
+/// 撤销尚未被消费的「忽略下一次变化」标记。
+///
+/// 当预期中的自触发写入最终没有真正改动剪贴板（例如焦点窗口没有选区，合成的
+/// 复制键什么也没写入）时调用，避免标记残留、误伤用户随后的第一次真实复制。
+pub fn clear_ignore_next_change() {
+    IGNORE_NEXT_CHANGE.store(false, Ordering::SeqCst);
+}
+
 /// 设置剪贴板文本（使用 arboard）
 pub fn set_clipboard_text(text: &str) -> Result<(), String> {
     let mut clipboard = Clipboard::new().map_err(|e| e.to_string())?;
@@ -189,6 +336,253 @@ pub fn set_clipboard_text(text: &str) -> Result<(), String> {
     Ok(())
 }
 
+/// 同时写入 HTML 与纯文本两种表示，让目标应用挑选其偏好的格式。
+fn set_clipboard_html(html: &str, shadow: &str) -> Result<(), String> {
+    let mut clipboard = Clipboard::new().map_err(|e| e.to_string())?;
+    clipboard
+        .set_html(html, Some(shadow))
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// 读取剪贴板中的 HTML 表示；没有该格式时返回 `None`。
+///
+/// Windows 上读取注册的 `CF_HTML` 格式并剥离其偏移量头部，只保留 `StartFragment`
+/// / `EndFragment` 之间的片段；非 Windows 平台 arboard 尚未暴露 HTML 读取接口，
+/// 暂不捕获（与 [`get_source_app`] 一样按平台分化）。
+#[cfg(target_os = "windows")]
+fn get_clipboard_html() -> Option<String> {
+    use std::os::windows::ffi::OsStrExt;
+
+    unsafe {
+        let name: Vec<u16> = std::ffi::OsStr::new("HTML Format")
+            .encode_wide()
+            .chain(std::iter::once(0))
+            .collect();
+        let format = RegisterClipboardFormatW(name.as_ptr());
+        if format == 0 {
+            return None;
+        }
+
+        if OpenClipboard(ptr::null_mut()) == 0 {
+            return None;
+        }
+
+        let result = (|| {
+            let handle = GetClipboardData(format);
+            if handle.is_null() {
+                return None;
+            }
+            let size = GlobalSize(handle);
+            if size == 0 {
+                return None;
+            }
+            let locked = GlobalLock(handle);
+            if locked.is_null() {
+                return None;
+            }
+            let bytes = std::slice::from_raw_parts(locked as *const u8, size);
+            // CF_HTML 以 UTF-8 编码，通常带一个结尾 NUL，截断到首个 NUL 为止。
+            let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+            // 在解锁前完成抽取（parse_cf_html 返回的是拷贝）。
+            let fragment = parse_cf_html(&bytes[..end]);
+            let _ = GlobalUnlock(handle);
+            fragment
+        })();
+
+        let _ = CloseClipboard();
+        result
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn get_clipboard_html() -> Option<String> {
+    None
+}
+
+/// 从 `CF_HTML` 负载中抽出真正的 HTML 片段。
+///
+/// 头部形如 `StartFragment:0000000141` 的各字段给出的是相对整个负载的字节偏移，
+/// 因此只能在原始字节上定位与切片（先转字符串会因非法 UTF-8 被替换而错位）；
+/// 优先取 `StartFragment`/`EndFragment` 之间的片段，其次退回 `StartHTML`/`EndHTML`，
+/// 仍失败时直接截取首个 `<` 之后的内容。
+#[cfg(target_os = "windows")]
+fn parse_cf_html(raw: &[u8]) -> Option<String> {
+    fn offset(raw: &[u8], key: &[u8]) -> Option<usize> {
+        let start = raw.windows(key.len()).position(|w| w == key)? + key.len();
+        let digits: Vec<u8> = raw[start..]
+            .iter()
+            .copied()
+            .take_while(u8::is_ascii_digit)
+            .collect();
+        std::str::from_utf8(&digits).ok()?.parse().ok()
+    }
+
+    for (start_key, end_key) in [
+        (&b"StartFragment:"[..], &b"EndFragment:"[..]),
+        (&b"StartHTML:"[..], &b"EndHTML:"[..]),
+    ] {
+        if let (Some(start), Some(end)) = (offset(raw, start_key), offset(raw, end_key)) {
+            if start <= end && end <= raw.len() {
+                let fragment = String::from_utf8_lossy(&raw[start..end]);
+                let trimmed = fragment.trim();
+                if !trimmed.is_empty() {
+                    return Some(trimmed.to_string());
+                }
+            }
+        }
+    }
+
+    let pos = raw.iter().position(|&b| b == b'<')?;
+    Some(String::from_utf8_lossy(&raw[pos..]).trim().to_string())
+}
+
+/// 快照剪贴板上的全部原始格式，仅在源应用附带私有格式（注册格式，id ≥ 0xC000）时
+/// 返回非空集合——普通文本/图片复制没有这类格式，走现有快路径即可。
+///
+/// 非 Windows 平台 arboard 未暴露原始多格式访问，恒返回 `None`。
+#[cfg(target_os = "windows")]
+fn capture_format_bundle() -> Option<Vec<ClipboardFormatBlob>> {
+    unsafe {
+        if OpenClipboard(ptr::null_mut()) == 0 {
+            return None;
+        }
+
+        let mut formats = Vec::new();
+        let mut has_proprietary = false;
+        let mut format = EnumClipboardFormats(0);
+        while format != 0 {
+            if is_snapshotable_format(format) {
+                if let Some(bytes) = read_clipboard_format_bytes(format) {
+                    if format >= 0xC000 {
+                        has_proprietary = true;
+                    }
+                    formats.push(ClipboardFormatBlob {
+                        format_id: format,
+                        name: clipboard_format_name(format),
+                        bytes,
+                    });
+                }
+            }
+            format = EnumClipboardFormats(format);
+        }
+
+        let _ = CloseClipboard();
+        if has_proprietary && !formats.is_empty() {
+            Some(formats)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn capture_format_bundle() -> Option<Vec<ClipboardFormatBlob>> {
+    None
+}
+
+/// 该格式的句柄是否为可 `GlobalLock` 的 HGLOBAL。
+///
+/// 位图（2）、图元文件（3）、调色板（9）、增强图元文件（14）以及 DSP/私有/GDI
+/// 句柄等不是内存句柄，对其加锁无意义，直接跳过。
+#[cfg(target_os = "windows")]
+fn is_snapshotable_format(format: u32) -> bool {
+    !matches!(format, 2 | 3 | 9 | 14)
+        && !(0x0080..=0x008F).contains(&format)
+        && !(0x0200..=0x03FF).contains(&format)
+}
+
+#[cfg(target_os = "windows")]
+unsafe fn read_clipboard_format_bytes(format: u32) -> Option<Vec<u8>> {
+    let handle = GetClipboardData(format);
+    if handle.is_null() {
+        return None;
+    }
+    let size = GlobalSize(handle);
+    if size == 0 {
+        return None;
+    }
+    let locked = GlobalLock(handle);
+    if locked.is_null() {
+        return None;
+    }
+    let bytes = std::slice::from_raw_parts(locked as *const u8, size).to_vec();
+    let _ = GlobalUnlock(handle);
+    Some(bytes)
+}
+
+#[cfg(target_os = "windows")]
+unsafe fn clipboard_format_name(format: u32) -> Option<String> {
+    // 标准格式没有可查询的名称，只有注册格式（≥ 0xC000）才有。
+    if format < 0xC000 {
+        return None;
+    }
+    let mut buffer = [0u16; 256];
+    let len = GetClipboardFormatNameW(format, buffer.as_mut_ptr(), buffer.len() as i32);
+    if len <= 0 {
+        return None;
+    }
+    Some(String::from_utf16_lossy(&buffer[..len as usize]))
+}
+
+/// 把一个格式集合原样回填到剪贴板，恢复源应用的全部格式。
+///
+/// 注册格式按名称重新注册以取得当前会话的 id；`SetClipboardData` 成功后系统接管
+/// 分配的 HGLOBAL。非 Windows 平台不支持。
+#[cfg(target_os = "windows")]
+fn set_clipboard_bundle(bundle: &[ClipboardFormatBlob]) -> Result<(), String> {
+    use std::os::windows::ffi::OsStrExt;
+
+    unsafe {
+        if OpenClipboard(ptr::null_mut()) == 0 {
+            return Err("failed to open clipboard".to_string());
+        }
+        if EmptyClipboard() == 0 {
+            let _ = CloseClipboard();
+            return Err("failed to empty clipboard".to_string());
+        }
+
+        for blob in bundle {
+            let format = match &blob.name {
+                Some(name) => {
+                    let wide: Vec<u16> = std::ffi::OsStr::new(name)
+                        .encode_wide()
+                        .chain(std::iter::once(0))
+                        .collect();
+                    let id = RegisterClipboardFormatW(wide.as_ptr());
+                    if id == 0 {
+                        blob.format_id
+                    } else {
+                        id
+                    }
+                }
+                None => blob.format_id,
+            };
+
+            let hmem = GlobalAlloc(GMEM_MOVEABLE, blob.bytes.len());
+            if hmem.is_null() {
+                continue;
+            }
+            let locked = GlobalLock(hmem);
+            if locked.is_null() {
+                continue;
+            }
+            std::ptr::copy_nonoverlapping(blob.bytes.as_ptr(), locked as *mut u8, blob.bytes.len());
+            let _ = GlobalUnlock(hmem);
+            // 成功后所有权移交系统；失败时仅泄漏一个小块，此处从简不回收。
+            SetClipboardData(format, hmem);
+        }
+
+        let _ = CloseClipboard();
+        Ok(())
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn set_clipboard_bundle(_bundle: &[ClipboardFormatBlob]) -> Result<(), String> {
+    Err("clipboard bundles are only supported on Windows".to_string())
+}
+
 fn decode_png_rgba(png_bytes: &[u8]) -> Result<(usize, usize, Vec<u8>), String> {
     let image = image::load_from_memory_with_format(png_bytes, ImageFormat::Png)
         .map_err(|e| e.to_string())?;
@@ -329,7 +723,7 @@ fn is_monitor_session_active(session_id: u64) -> bool {
 
 fn build_startup_signature() -> Option<String> {
     Clipboard::new().ok().and_then(|mut clipboard| {
-        if ENABLE_IMAGE_RECORDING {
+        if image_recording_enabled() {
             if let Ok(image) = clipboard.get_image() {
                 return Some(image_signature(
                     image.width,
@@ -338,6 +732,9 @@ fn build_startup_signature() -> Option<String> {
                 ));
             }
         }
+        if let Some(html) = get_clipboard_html() {
+            return Some(format!("html:{}", html));
+        }
         if let Ok(text) = clipboard.get_text() {
             return Some(format!("text:{}", text));
         }
@@ -347,6 +744,8 @@ fn build_startup_signature() -> Option<String> {
 
 fn emit_history_changed(app: &AppHandle) {
     let _ = app.emit("history-changed", ());
+    // 捕获到新条目后刷新托盘「最近」子菜单，使其与历史保持同步。
+    let _ = crate::tray::refresh_tray_menu(app);
 }
 
 fn process_clipboard_change(
@@ -365,21 +764,33 @@ fn process_clipboard_change(
         }
     };
 
-    if ENABLE_IMAGE_RECORDING {
+    if image_recording_enabled() {
         if let Ok(image) = clipboard.get_image() {
             let raw = image.bytes.as_ref();
-            let signature = image_signature(image.width, image.height, raw);
+            let new_width = image.width;
+            let new_height = image.height;
+            let new_hash = image_ahash(new_width, new_height, raw);
             let should_ignore = IGNORE_NEXT_CHANGE.swap(false, Ordering::SeqCst);
-            let changed = if let Ok(mut last) = last_signature.lock() {
-                if *last == Some(signature.clone()) {
-                    false
-                } else {
-                    *last = Some(signature);
-                    true
-                }
-            } else {
-                false
-            };
+
+            // 先只判断是否为新图片，暂不改 last——待真正入库后再记指纹，避免被节流、
+            // 超限或写库失败污染，否则后续同一张图片会被永久误判为重复。
+            let changed = last_signature
+                .lock()
+                .ok()
+                .map(|last| {
+                    // 尺寸一致且 aHash 汉明距离在阈值内，视为同一张图片的重复复制。
+                    !last
+                        .as_deref()
+                        .and_then(parse_image_signature)
+                        .map(|(lw, lh, lhash)| {
+                            lw == new_width
+                                && lh == new_height
+                                && image_hash_distance(lhash, new_hash)
+                                    <= IMAGE_HASH_MATCH_DISTANCE
+                        })
+                        .unwrap_or(false)
+                })
+                .unwrap_or(false);
 
             if changed && !should_ignore {
                 if raw.len() > MAX_IMAGE_BYTES {
@@ -392,9 +803,16 @@ fn process_clipboard_change(
                     return;
                 }
 
-                if let Ok(record) = build_image_record(image.width, image.height, raw) {
+                if let Ok(record) = build_image_record(new_width, new_height, raw) {
                     if crate::database::add_record(record).is_ok() {
                         LAST_IMAGE_RECORD_MS.store(now, Ordering::SeqCst);
+                        // 入库成功后才记下指纹。
+                        if let Ok(mut last) = last_signature.lock() {
+                            *last = Some(format!(
+                                "image:{}:{}:{}",
+                                new_width, new_height, new_hash
+                            ));
+                        }
                         emit_history_changed(app);
                     }
                 }
@@ -403,6 +821,45 @@ fn process_clipboard_change(
         }
     }
 
+    // 富文本优先：若剪贴板带 HTML 表示，整条记为 html 类型，保留 HTML 与纯文本影子。
+    if let Some(html) = get_clipboard_html() {
+        // 这次变化已被本分支消费，无论最终是否入库都要吃掉「忽略下一次」标记，
+        // 以免空影子提前返回时把标记残留给用户的下一次真实复制。
+        let should_ignore = IGNORE_NEXT_CHANGE.swap(false, Ordering::SeqCst);
+
+        let shadow = clipboard
+            .get_text()
+            .ok()
+            .filter(|t| !t.trim().is_empty())
+            .unwrap_or_else(|| html_to_plain_shadow(&html));
+        if shadow.trim().is_empty() {
+            return;
+        }
+
+        let signature = format!("html:{}", html);
+        let changed = if let Ok(mut last) = last_signature.lock() {
+            if *last == Some(signature.clone()) {
+                false
+            } else {
+                *last = Some(signature);
+                true
+            }
+        } else {
+            false
+        };
+
+        if changed && !should_ignore {
+            let mut record = build_html_record(html, shadow.clone());
+            record.bundle = capture_format_bundle();
+            if crate::database::add_record(record).is_ok() {
+                emit_history_changed(app);
+                // 纯文本影子推送给其它设备（同步通道目前只承载文本）。
+                crate::sync::push(&shadow);
+            }
+        }
+        return;
+    }
+
     if let Ok(text) = clipboard.get_text() {
         if text.trim().is_empty() {
             return;
@@ -422,9 +879,12 @@ fn process_clipboard_change(
         };
 
         if changed && !should_ignore {
-            let record = build_text_record(text);
+            let mut record = build_text_record(text.clone());
+            record.bundle = capture_format_bundle();
             if crate::database::add_record(record).is_ok() {
                 emit_history_changed(app);
+                // 把本地新捕获的文本推送给其它设备（未配置同步时为空操作）。
+                crate::sync::push(&text);
             }
         }
     }
@@ -517,9 +977,17 @@ pub async fn start_monitoring(app: AppHandle) -> Result<(), String> {
         return Ok(());
     }
 
+    // 以持久化设置初始化图片记录开关，随后 save_app_settings 可在运行时改动它。
+    if let Ok(settings) = crate::database::get_settings() {
+        set_image_recording_enabled(settings.enable_image_recording);
+    }
+
     let session_id = next_monitor_session_id();
     let last_signature = Arc::new(Mutex::new(build_startup_signature()));
 
+    // 若已配置跨设备同步，随监听一并启动轮询。
+    crate::sync::start(&app);
+
     #[cfg(target_os = "windows")]
     {
         spawn_event_driven_monitor(last_signature, session_id, app);
@@ -533,29 +1001,160 @@ pub async fn start_monitoring(app: AppHandle) -> Result<(), String> {
     Ok(())
 }
 
-/// 使用 enigo 模拟 Ctrl+V 粘贴到焦点窗口
-fn send_paste_shortcut(focus_settle_ms: u64) {
-    let mut enigo = Enigo::new();
-
-    if focus_settle_ms > 0 {
-        std::thread::sleep(Duration::from_millis(focus_settle_ms));
-    }
+/// 粘贴行为的可选项，由前端按需传入。
+///
+/// 缺省（`None` 或全部字段为默认值）时等价于过去的行为：按平台默认的 Ctrl/Cmd+V
+/// 原样回填记录。
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PasteOptions {
+    /// 为 `true` 时 `html`/富文本记录只写纯文本影子，目标应用不会收到任何样式。
+    #[serde(default)]
+    pub plain_text: bool,
+    /// 自定义粘贴加速键，如 `"Ctrl+Shift+V"`；为空时使用平台默认的 Ctrl/Cmd+V。
+    #[serde(default)]
+    pub accelerator: Option<String>,
+}
 
+/// 平台默认的粘贴加速键序列：macOS 为 Cmd+V，其余为 Ctrl+V。
+fn default_paste_sequence() -> (Vec<Key>, Key) {
     #[cfg(target_os = "macos")]
     let modifier = Key::Meta;
     #[cfg(not(target_os = "macos"))]
     let modifier = Key::Control;
+    (vec![modifier], Key::Layout('v'))
+}
 
-    enigo.key_down(modifier);
-    std::thread::sleep(Duration::from_millis(PASTE_KEY_STEP_MS));
-    enigo.key_click(Key::Layout('v'));
+/// 把加速键字符串解析成 `(修饰键, 主键)` 的 enigo 按键序列。
+///
+/// 语法与 tao 的加速键一致：以 `+` 连接，末段为主键，其余为修饰键，大小写不敏感。
+/// 支持字母数字、扩展标点（`, - . = ; / \ [ ]`）、Space/Tab 以及 F1–F24 功能键；
+/// 无法识别时返回带原始串的错误，便于设置界面精确提示。
+fn parse_accelerator(accelerator: &str) -> Result<(Vec<Key>, Key), String> {
+    let tokens: Vec<&str> = accelerator
+        .split('+')
+        .map(|t| t.trim())
+        .filter(|t| !t.is_empty())
+        .collect();
+    if tokens.is_empty() {
+        return Err(format!("invalid accelerator '{}': empty", accelerator));
+    }
+
+    let (key_token, modifier_tokens) = tokens.split_last().unwrap();
+    let mut modifiers = Vec::with_capacity(modifier_tokens.len());
+    for token in modifier_tokens {
+        modifiers.push(parse_modifier(token).ok_or_else(|| {
+            format!("invalid accelerator '{}': unknown modifier '{}'", accelerator, token)
+        })?);
+    }
+    let key = parse_accelerator_key(key_token)
+        .ok_or_else(|| format!("invalid accelerator '{}': unknown key '{}'", accelerator, key_token))?;
+    Ok((modifiers, key))
+}
+
+fn parse_modifier(token: &str) -> Option<Key> {
+    match token.to_ascii_lowercase().as_str() {
+        "ctrl" | "control" => Some(Key::Control),
+        "shift" => Some(Key::Shift),
+        "alt" | "option" => Some(Key::Alt),
+        "cmd" | "command" | "super" | "meta" | "win" | "windows" => Some(Key::Meta),
+        _ => None,
+    }
+}
+
+fn parse_accelerator_key(token: &str) -> Option<Key> {
+    match token.to_ascii_lowercase().as_str() {
+        "space" => return Some(Key::Space),
+        "tab" => return Some(Key::Tab),
+        other => {
+            // F1–F24 功能键。
+            if let Some(num) = other.strip_prefix('f') {
+                if let Ok(n) = num.parse::<u8>() {
+                    return function_key(n);
+                }
+            }
+        }
+    }
+
+    // 单字符主键：字母数字与 tao 支持的扩展标点。
+    let mut chars = token.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None)
+            if c.is_ascii_alphanumeric()
+                || matches!(c, ',' | '-' | '.' | '=' | ';' | '/' | '\\' | '[' | ']') =>
+        {
+            Some(Key::Layout(c.to_ascii_lowercase()))
+        }
+        _ => None,
+    }
+}
+
+fn function_key(n: u8) -> Option<Key> {
+    match n {
+        1 => Some(Key::F1),
+        2 => Some(Key::F2),
+        3 => Some(Key::F3),
+        4 => Some(Key::F4),
+        5 => Some(Key::F5),
+        6 => Some(Key::F6),
+        7 => Some(Key::F7),
+        8 => Some(Key::F8),
+        9 => Some(Key::F9),
+        10 => Some(Key::F10),
+        11 => Some(Key::F11),
+        12 => Some(Key::F12),
+        13 => Some(Key::F13),
+        14 => Some(Key::F14),
+        15 => Some(Key::F15),
+        16 => Some(Key::F16),
+        17 => Some(Key::F17),
+        18 => Some(Key::F18),
+        19 => Some(Key::F19),
+        20 => Some(Key::F20),
+        21 => Some(Key::F21),
+        22 => Some(Key::F22),
+        23 => Some(Key::F23),
+        24 => Some(Key::F24),
+        _ => None,
+    }
+}
+
+/// 使用 enigo 模拟加速键粘贴到焦点窗口。
+///
+/// 先按下全部修饰键，点击主键，再以相反顺序松开修饰键，保证组合键被目标应用
+/// 正确识别。
+fn send_paste_shortcut(focus_settle_ms: u64, modifiers: &[Key], key: Key) {
+    let mut enigo = Enigo::new();
+
+    if focus_settle_ms > 0 {
+        std::thread::sleep(Duration::from_millis(focus_settle_ms));
+    }
+
+    for modifier in modifiers {
+        enigo.key_down(*modifier);
+        std::thread::sleep(Duration::from_millis(PASTE_KEY_STEP_MS));
+    }
+    enigo.key_click(key);
     std::thread::sleep(Duration::from_millis(PASTE_KEY_STEP_MS));
-    enigo.key_up(modifier);
+    for modifier in modifiers.iter().rev() {
+        enigo.key_up(*modifier);
+    }
 }
 
 /// 一次性完成复制并粘贴：写剪贴板 -> 隐藏窗口 -> 发送粘贴按键
 #[tauri::command]
-pub async fn paste_record_content(app: AppHandle, id: i64) -> Result<(), String> {
+pub async fn paste_record_content(
+    app: AppHandle,
+    id: i64,
+    options: Option<PasteOptions>,
+) -> Result<(), String> {
+    let options = options.unwrap_or_default();
+    // 加速键在进入粘贴流程前解析，无效串直接回报而不改动剪贴板。
+    let (modifiers, key) = match options.accelerator.as_deref() {
+        Some(acc) if !acc.trim().is_empty() => parse_accelerator(acc)?,
+        _ => default_paste_sequence(),
+    };
+
     with_paste_in_progress(|| {
         let record = crate::database::get_record_by_id(id)
             .map_err(|e| e.to_string())?
@@ -565,12 +1164,24 @@ pub async fn paste_record_content(app: AppHandle, id: i64) -> Result<(), String>
         // 前端点击记录时，避免监听器把本次剪贴板写入再次记录
         IGNORE_NEXT_CHANGE.store(true, Ordering::SeqCst);
 
-        if is_image {
+        // 纯文本模式：对任何带文本的记录只写纯文本影子，目标不会收到样式或私有格式。
+        if options.plain_text && !is_image {
+            set_clipboard_text(&record.content)?;
+        } else if let Some(bundle) = record.bundle.as_deref().filter(|b| !b.is_empty()) {
+            // 带原始多格式快照的记录整组回填，无损还原 Excel、矢量图等的私有格式。
+            set_clipboard_bundle(bundle)?;
+        } else if is_image {
             let data = record
                 .image_data
                 .ok_or_else(|| "image record missing image_data".to_string())?;
             let (width, height, bytes) = decode_png_rgba(&data)?;
             set_clipboard_image_rgba(width, height, bytes)?;
+        } else if record.content_type == "html" {
+            // html 记录把 HTML 与纯文本影子一并写回，让目标应用挑选偏好的表示。
+            match record.html.as_deref() {
+                Some(html) => set_clipboard_html(html, &record.content)?,
+                None => set_clipboard_text(&record.content)?,
+            }
         } else {
             set_clipboard_text(&record.content)?;
         }
@@ -589,11 +1200,15 @@ pub async fn paste_record_content(app: AppHandle, id: i64) -> Result<(), String>
         }
 
         // 给系统时间切换焦点到原目标窗口（图片略高于文本）
-        send_paste_shortcut(if is_image {
-            PASTE_SETTLE_MS_IMAGE
-        } else {
-            PASTE_SETTLE_MS_TEXT
-        });
+        send_paste_shortcut(
+            if is_image {
+                PASTE_SETTLE_MS_IMAGE
+            } else {
+                PASTE_SETTLE_MS_TEXT
+            },
+            &modifiers,
+            key,
+        );
 
         Ok(())
     })
@@ -3,10 +3,11 @@ use crate::database::{
     get_all_favorite_history, get_favorite_history, get_history, get_settings, save_settings,
     search_favorite_history, search_history, set_record_favorite, set_record_pinned,
 };
-use crate::models::{ClipboardRecord, Settings};
+use crate::models::{ClipboardRecord, SearchMode, Settings};
+use base64::Engine;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
-use tauri::AppHandle;
+use tauri::{AppHandle, Emitter};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct FavoriteTransferItem {
@@ -14,6 +15,12 @@ pub struct FavoriteTransferItem {
     pub content: String,
     pub is_pinned: bool,
     pub source_app: String,
+    /// v2 新增：图片收藏的 base64 编码字节；文本/链接/html 记录为 `None`。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image_data: Option<String>,
+    /// v2 新增：图片的原始 MIME（当前导出恒为 `image/png`）。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image_mime: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -27,23 +34,46 @@ pub struct FavoriteTransferPackage {
 pub struct FavoriteExportResult {
     pub count: i32,
     pub path: String,
+    /// 覆盖已有导出时上一版被轮转保存的路径，供前端提示“旧版本已保存到 …”。
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backup: Option<String>,
+}
+
+/// 导入时单张图片解码后的上限，超过则跳过该条而非整体失败。
+const MAX_IMPORT_IMAGE_BYTES: usize = 20 * 1024 * 1024;
+
+fn base64_engine() -> base64::engine::GeneralPurpose {
+    base64::engine::general_purpose::STANDARD
+}
+
+/// 图片内容哈希，用于收藏去重（图片记录 `content` 为空，无法按字符串判重）。
+fn image_hash(bytes: &[u8]) -> String {
+    use sha2::{Digest, Sha256};
+    let mut hasher = Sha256::new();
+    hasher.update(bytes);
+    format!("{:x}", hasher.finalize())
 }
 
 fn collect_favorites_package() -> Result<FavoriteTransferPackage, String> {
     let records = get_all_favorite_history().map_err(|e| e.to_string())?;
     let favorites = records
         .into_iter()
-        .filter(|r| r.content_type != "image")
-        .map(|r| FavoriteTransferItem {
-            content_type: r.content_type,
-            content: r.content,
-            is_pinned: r.is_pinned,
-            source_app: r.source_app,
+        .map(|r| {
+            let image_data = r.image_data.as_deref().map(|bytes| base64_engine().encode(bytes));
+            let image_mime = (r.content_type == "image").then(|| "image/png".to_string());
+            FavoriteTransferItem {
+                content_type: r.content_type,
+                content: r.content,
+                is_pinned: r.is_pinned,
+                source_app: r.source_app,
+                image_data,
+                image_mime,
+            }
         })
         .collect();
 
     Ok(FavoriteTransferPackage {
-        version: 1,
+        version: 2,
         exported_at: chrono::Local::now().to_rfc3339(),
         favorites,
     })
@@ -53,22 +83,67 @@ fn import_favorites_from_payload(payload: &str) -> Result<i32, String> {
     let parsed: FavoriteTransferPackage =
         serde_json::from_str(payload).map_err(|e| format!("invalid json: {}", e))?;
 
-    if parsed.version != 1 {
+    if !matches!(parsed.version, 1 | 2) {
         return Err(format!("unsupported version: {}", parsed.version));
     }
 
+    // 图片收藏按内容哈希去重：先收下库里已有的，导入过程中再逐条补充，避免批内重复。
+    let mut image_hashes: std::collections::HashSet<String> = get_all_favorite_history()
+        .map_err(|e| e.to_string())?
+        .iter()
+        .filter_map(|r| r.image_data.as_deref().map(image_hash))
+        .collect();
+
     let mut imported: i32 = 0;
     for item in parsed.favorites {
-        let content = item.content.trim();
-        if content.is_empty() {
-            continue;
-        }
-
         let mut content_type = item.content_type.trim().to_string();
         if content_type.is_empty() {
             content_type = "text".to_string();
         }
 
+        let source_app = if item.source_app.trim().is_empty() {
+            "Import".to_string()
+        } else {
+            item.source_app.trim().to_string()
+        };
+
+        if content_type == "image" {
+            // 图片仅 v2 支持：解码 base64，过大或损坏则跳过而非整体失败。
+            let Some(encoded) = item.image_data.as_deref() else {
+                continue;
+            };
+            let bytes = match base64_engine().decode(encoded.trim()) {
+                Ok(bytes) => bytes,
+                Err(_) => continue,
+            };
+            if bytes.is_empty() || bytes.len() > MAX_IMPORT_IMAGE_BYTES {
+                continue;
+            }
+            if !image_hashes.insert(image_hash(&bytes)) {
+                continue;
+            }
+
+            let record = ClipboardRecord {
+                id: 0,
+                content_type,
+                content: String::new(),
+                html: None,
+                bundle: None,
+                image_data: Some(bytes),
+                is_favorite: true,
+                is_pinned: item.is_pinned,
+                source_app,
+                created_at: chrono::Local::now().to_rfc3339(),
+            };
+            add_record(record).map_err(|e| e.to_string())?;
+            imported += 1;
+            continue;
+        }
+
+        let content = item.content.trim();
+        if content.is_empty() {
+            continue;
+        }
         if !matches!(content_type.as_str(), "text" | "link" | "html") {
             content_type = "text".to_string();
         }
@@ -77,16 +152,12 @@ fn import_favorites_from_payload(payload: &str) -> Result<i32, String> {
             continue;
         }
 
-        let source_app = if item.source_app.trim().is_empty() {
-            "Import".to_string()
-        } else {
-            item.source_app.trim().to_string()
-        };
-
         let record = ClipboardRecord {
             id: 0,
             content_type,
             content: content.to_string(),
+            html: None,
+            bundle: None,
             image_data: None,
             is_favorite: true,
             is_pinned: item.is_pinned,
@@ -108,8 +179,13 @@ pub fn get_history_records(limit: i32, offset: i32) -> Result<Vec<ClipboardRecor
 }
 
 #[tauri::command]
-pub fn search_records(keyword: String, limit: i32) -> Result<Vec<ClipboardRecord>, String> {
-    let records = search_history(&keyword, limit).map_err(|e| e.to_string())?;
+pub fn search_records(
+    keyword: String,
+    mode: Option<SearchMode>,
+    limit: i32,
+) -> Result<Vec<ClipboardRecord>, String> {
+    let records =
+        search_history(&keyword, mode.unwrap_or_default(), limit).map_err(|e| e.to_string())?;
     Ok(records)
 }
 
@@ -122,9 +198,11 @@ pub fn get_favorite_records(limit: i32, offset: i32) -> Result<Vec<ClipboardReco
 #[tauri::command]
 pub fn search_favorite_records(
     keyword: String,
+    mode: Option<SearchMode>,
     limit: i32,
 ) -> Result<Vec<ClipboardRecord>, String> {
-    let records = search_favorite_history(&keyword, limit).map_err(|e| e.to_string())?;
+    let records = search_favorite_history(&keyword, mode.unwrap_or_default(), limit)
+        .map_err(|e| e.to_string())?;
     Ok(records)
 }
 
@@ -141,6 +219,8 @@ pub fn add_custom_favorite_record(content: String) -> Result<i64, String> {
         id: 0,
         content_type: "text".to_string(),
         content: text.to_string(),
+        html: None,
+        bundle: None,
         image_data: None,
         is_favorite: true,
         is_pinned: false,
@@ -181,6 +261,18 @@ pub fn set_record_pinned_state(id: i64, pinned: bool) -> Result<(), String> {
     Ok(())
 }
 
+/// 设置主口令：写入验证串并登记密钥，使此后所有连接自动加密。
+#[tauri::command]
+pub fn set_master_passphrase(passphrase: String) -> Result<(), String> {
+    crate::database::set_master_passphrase(&passphrase)
+}
+
+/// 用口令解锁已加密的数据库；口令错误时返回可区分的错误。
+#[tauri::command]
+pub fn unlock_database(passphrase: String) -> Result<(), String> {
+    crate::database::unlock(&passphrase)
+}
+
 #[tauri::command]
 pub fn export_favorites_to_path(path: String) -> Result<FavoriteExportResult, String> {
     let path = path.trim();
@@ -206,10 +298,12 @@ pub fn export_favorites_to_path(path: String) -> Result<FavoriteExportResult, St
     let payload = collect_favorites_package()?;
     let count = payload.favorites.len() as i32;
     let json = serde_json::to_string_pretty(&payload).map_err(|e| e.to_string())?;
-    std::fs::write(&output, json).map_err(|e| e.to_string())?;
+    // 原子写入并轮转备份：中途崩溃不会留下空文件，覆盖旧导出时保留上一版可回滚。
+    let backup = crate::atomic::write_default(&output, json.as_bytes())?;
     Ok(FavoriteExportResult {
         count,
         path: output.to_string_lossy().to_string(),
+        backup: backup.map(|p| p.to_string_lossy().to_string()),
     })
 }
 
@@ -231,20 +325,38 @@ pub fn get_app_settings() -> Result<Settings, String> {
 
 #[tauri::command]
 pub fn save_app_settings(app: AppHandle, settings: Settings) -> Result<(), String> {
-    // 1. 先尝试注册快捷键，获取标准化后的字符串
-    let normalized = crate::hotkey::register_hotkey(&app, &settings.hotkey)?;
+    let updated = apply_settings(&app, settings)?;
+    // UI 发起的改动，把规整后的结果同步写回配置文件，让文件与库收敛。
+    crate::config::write_config(&updated);
+    Ok(())
+}
+
+/// 应用一份设置并把副作用落地：注册快捷键、写库、刷新图片开关与自启动，最后向
+/// 前端广播主题与整体设置变更，实现免重启热更新。
+///
+/// UI 的 [`save_app_settings`] 与配置文件热重载共用这套步骤，确保两条入口行为一致；
+/// 返回经规整（如快捷键标准化）后的设置，供调用方写回源头。
+pub fn apply_settings(app: &AppHandle, settings: Settings) -> Result<Settings, String> {
+    // 1. 先尝试注册全部动作的快捷键，就地获取标准化后的字符串
     let mut updated = settings;
-    updated.hotkey = normalized;
+    crate::hotkey::apply_all_hotkeys(app, &mut updated)?;
 
     // 2. 先保存设置到数据库（最重要的一步，不能被后续操作阻断）
     save_settings(&updated).map_err(|e| e.to_string())?;
 
-    // 3. 自启动设置为尽力而为，失败不阻断（开发模式下 disable 常因文件不存在而失败）
-    if let Err(e) = crate::autostart::set_enabled(&app, updated.auto_start) {
+    // 3. 图片记录开关即时生效，无需重启监听。
+    crate::clipboard::set_image_recording_enabled(updated.enable_image_recording);
+
+    // 4. 自启动设置为尽力而为，失败不阻断（开发模式下 disable 常因文件不存在而失败）
+    if let Err(e) = crate::autostart::set_enabled(app, updated.auto_start) {
         eprintln!("[WARN] auto start toggle failed (non-fatal): {}", e);
     }
 
-    Ok(())
+    // 5. 主题与整体设置推送给前端，便于界面即时刷新。
+    let _ = app.emit("theme-changed", updated.theme.clone());
+    let _ = app.emit("settings-changed", updated.clone());
+
+    Ok(updated)
 }
 
 #[tauri::command]
@@ -261,6 +373,8 @@ pub fn set_frontend_ready(app: AppHandle) {
 }
 
 #[tauri::command]
-pub fn open_url(url: String) -> Result<(), String> {
-    opener::open(&url).map_err(|e| e.to_string())
+pub fn set_pinned_state(app: AppHandle, pinned: bool) -> Result<(), String> {
+    crate::tray::set_pinned_mode(&app, pinned);
+    Ok(())
 }
+
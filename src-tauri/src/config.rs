@@ -0,0 +1,125 @@
+//! 文件式设置的热重载。
+//!
+//! 除了数据库里的 [`Settings`]，再维护一份与其同目录的 JSON 配置文件。后台监听该
+//! 文件的外部编辑，解析出新设置后与当前库内设置做 diff，复用
+//! [`crate::commands::apply_settings`] 的全部副作用（重绑快捷键、推送主题、切换自启动
+//! 等）即时生效，免重启。数据库仍是真相来源：每次应用后把规整结果写回文件，使 UI 与
+//! 文件两条入口最终收敛。
+
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use tauri::AppHandle;
+
+use crate::models::Settings;
+
+/// 合并一段时间内的连续写入，避免编辑器分多次落盘触发多次重载。
+const DEBOUNCE_MS: u64 = 200;
+
+/// 最近一次由我们自己写入文件的内容，用于过滤掉自写事件引发的重载回环。
+static LAST_WRITTEN: OnceLock<Mutex<Option<Vec<u8>>>> = OnceLock::new();
+
+fn last_written() -> &'static Mutex<Option<Vec<u8>>> {
+    LAST_WRITTEN.get_or_init(|| Mutex::new(None))
+}
+
+/// 启动配置文件监听。文件缺失时先用当前库内设置初始化一份，方便用户直接编辑。
+pub fn start_watcher(app: AppHandle) {
+    let path = crate::database::config_file_path();
+    if !path.exists() {
+        if let Ok(settings) = crate::database::get_settings() {
+            write_config(&settings);
+        }
+    }
+    std::thread::spawn(move || run_watch_loop(app, path));
+}
+
+/// 把设置序列化写入配置文件；同时记下内容，供监听循环跳过自写事件。
+pub fn write_config(settings: &Settings) {
+    let path = crate::database::config_file_path();
+    let Ok(bytes) = serde_json::to_vec_pretty(settings) else {
+        return;
+    };
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    if let Ok(mut guard) = last_written().lock() {
+        *guard = Some(bytes.clone());
+    }
+    // 走原子写入，崩溃或写满磁盘都不会留下半截配置文件。
+    let _ = crate::atomic::write_default(&path, &bytes);
+}
+
+fn run_watch_loop(app: AppHandle, path: PathBuf) {
+    let (tx, rx) = std::sync::mpsc::channel();
+    let mut watcher = match RecommendedWatcher::new(
+        move |res| {
+            let _ = tx.send(res);
+        },
+        notify::Config::default(),
+    ) {
+        Ok(watcher) => watcher,
+        Err(e) => {
+            eprintln!("[WARN] config watcher init failed (non-fatal): {}", e);
+            return;
+        }
+    };
+
+    // 监听所在目录而非单个文件：原子写会先写临时文件再 rename，盯着文件本身会漏事件。
+    let watch_dir = path
+        .parent()
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("."));
+    if let Err(e) = watcher.watch(&watch_dir, RecursiveMode::NonRecursive) {
+        eprintln!("[WARN] config watch failed (non-fatal): {}", e);
+        return;
+    }
+
+    loop {
+        // 阻塞等第一条事件，再把随后 ~200ms 内的事件吃掉，合并成一次重载。
+        if rx.recv().is_err() {
+            break;
+        }
+        while rx.recv_timeout(Duration::from_millis(DEBOUNCE_MS)).is_ok() {}
+        reload(&app, &path);
+    }
+}
+
+fn reload(app: &AppHandle, path: &Path) {
+    let Ok(bytes) = std::fs::read(path) else {
+        return;
+    };
+
+    // 过滤自写事件：内容与我们最近写入的一致则无需处理。
+    if last_written()
+        .lock()
+        .ok()
+        .map(|g| g.as_deref() == Some(bytes.as_slice()))
+        .unwrap_or(false)
+    {
+        return;
+    }
+
+    let incoming: Settings = match serde_json::from_slice(&bytes) {
+        Ok(settings) => settings,
+        Err(e) => {
+            eprintln!("[WARN] ignoring malformed config file: {}", e);
+            return;
+        }
+    };
+
+    let Ok(current) = crate::database::get_settings() else {
+        return;
+    };
+    if current == incoming {
+        return;
+    }
+
+    match crate::commands::apply_settings(app, incoming) {
+        // 规整结果写回文件，使库与文件收敛（如快捷键被标准化时）。
+        Ok(reconciled) => write_config(&reconciled),
+        Err(e) => eprintln!("[WARN] failed to apply config file: {}", e),
+    }
+}
@@ -1,10 +1,94 @@
-use crate::models::{ClipboardRecord, Settings};
-use rusqlite::{params, Connection, OptionalExtension, Result};
+use crate::models::{ChangeAction, ChangeEvent, ClipboardRecord, HistoryFilter, SearchMode, Settings};
+use rusqlite::{params, Connection, OptionalExtension, Result, ToSql};
+use std::ops::Deref;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{channel, Receiver, Sender};
 use std::sync::{Mutex, MutexGuard, OnceLock};
 
 const DB_FILE: &str = "snappaste.db";
-static DB_CONNECTION: OnceLock<Mutex<Connection>> = OnceLock::new();
+
+/// 空闲表中保留的读连接上限（同时借出的读连接数不受此限）；写连接始终只有一条。
+const MAX_READERS: usize = 4;
+/// `PRAGMA busy_timeout` 的默认毫秒数，可经 [`Settings`] 调整。
+const DEFAULT_BUSY_TIMEOUT_MS: u64 = 5_000;
+
+/// 生效中的 `busy_timeout`（毫秒），新建连接时读取；[`configure_busy_timeout`]
+/// 更新它并即时应用到写连接。
+static BUSY_TIMEOUT_MS: AtomicU64 = AtomicU64::new(DEFAULT_BUSY_TIMEOUT_MS);
+
+/// 变更事件的订阅者；广播时顺带丢弃接收端已被释放的发送端。
+static CHANGE_SUBSCRIBERS: OnceLock<Mutex<Vec<Sender<ChangeEvent>>>> = OnceLock::new();
+/// update_hook 暂存、commit_hook 提交后统一广播的待发事件缓冲区。
+static PENDING_CHANGES: OnceLock<Mutex<Vec<ChangeEvent>>> = OnceLock::new();
+
+fn change_subscribers() -> &'static Mutex<Vec<Sender<ChangeEvent>>> {
+    CHANGE_SUBSCRIBERS.get_or_init(|| Mutex::new(Vec::new()))
+}
+
+fn pending_changes() -> &'static Mutex<Vec<ChangeEvent>> {
+    PENDING_CHANGES.get_or_init(|| Mutex::new(Vec::new()))
+}
+
+/// 订阅 `clipboard_history` 的行级变更。
+///
+/// 返回的 [`Receiver`] 会在每次事务提交后收到该事务内的全部 [`ChangeEvent`]，
+/// 包括 [`apply_retention_policy`] 做的删除。接收端被丢弃后，对应发送端会在下一
+/// 次广播时自动从订阅表移除。
+pub fn subscribe() -> Receiver<ChangeEvent> {
+    let (tx, rx) = channel();
+    if let Ok(mut subs) = change_subscribers().lock() {
+        subs.push(tx);
+    }
+    rx
+}
+
+/// 在连接上登记 update/commit 钩子。
+///
+/// 钩子在持有连接互斥锁的线程上同步触发，因此 update_hook 只把行级变更压入
+/// [`pending_changes`] 缓冲区，绝不回查连接——否则会与 [`lock_db_connection`]
+/// 死锁。commit_hook 在事务提交时一次性把缓冲区广播给订阅者并返回 `false` 放行提交。
+fn install_change_hooks(conn: &Connection) {
+    use rusqlite::hooks::Action;
+
+    conn.update_hook(Some(
+        |action: Action, db: &str, table: &str, rowid: i64| {
+            // 仅关注主库的历史表；ATTACH 出来的库（如加密迁移）不应外泄事件。
+            if db != "main" || table != "clipboard_history" {
+                return;
+            }
+            let action = match action {
+                Action::SQLITE_INSERT => ChangeAction::Insert,
+                Action::SQLITE_UPDATE => ChangeAction::Update,
+                Action::SQLITE_DELETE => ChangeAction::Delete,
+                _ => return,
+            };
+            if let Ok(mut pending) = pending_changes().lock() {
+                pending.push(ChangeEvent { action, rowid });
+            }
+        },
+    ));
+
+    conn.commit_hook(Some(|| {
+        let drained = pending_changes()
+            .lock()
+            .map(|mut pending| std::mem::take(&mut *pending))
+            .unwrap_or_default();
+        if !drained.is_empty() {
+            if let Ok(mut subs) = change_subscribers().lock() {
+                subs.retain(|tx| drained.iter().all(|event| tx.send(*event).is_ok()));
+            }
+        }
+        false
+    }));
+
+    // 事务回滚时丢弃已暂存的行变更，避免它们在下一次提交时作为幽灵事件外发。
+    conn.rollback_hook(Some(|| {
+        if let Ok(mut pending) = pending_changes().lock() {
+            pending.clear();
+        }
+    }));
+}
 
 #[cfg(target_os = "windows")]
 fn preferred_windows_db_path() -> Option<PathBuf> {
@@ -34,6 +118,41 @@ fn get_db_path() -> PathBuf {
     path
 }
 
+/// 已配置的主口令（仅驻留内存，绝不落盘）。必须在首次打开连接之前设置。
+static MASTER_PASSPHRASE: OnceLock<Mutex<Option<String>>> = OnceLock::new();
+
+fn passphrase_slot() -> &'static Mutex<Option<String>> {
+    MASTER_PASSPHRASE.get_or_init(|| Mutex::new(None))
+}
+
+fn current_passphrase() -> Option<String> {
+    passphrase_slot().lock().ok().and_then(|g| g.clone())
+}
+
+/// 验证口令的盐/哈希文件路径（与数据库同目录，明文存放，不含口令本身）。
+fn security_file_path() -> PathBuf {
+    let mut path = get_db_path();
+    path.set_file_name("snappaste.key");
+    path
+}
+
+/// 文件式设置的路径（与数据库同目录），供用户直接编辑以热重载配置。
+pub fn config_file_path() -> PathBuf {
+    let mut path = get_db_path();
+    path.set_file_name("snappaste.config.json");
+    path
+}
+
+/// 在任何其它语句触及 schema 之前，对连接应用 SQLCipher 密钥。
+fn apply_key(conn: &Connection, passphrase: &str) -> Result<(), rusqlite::Error> {
+    // 单引号按 SQL 规则翻倍转义后再拼入 PRAGMA。
+    let escaped = passphrase.replace('\'', "''");
+    conn.execute_batch(&format!(
+        "PRAGMA key = '{}';\nPRAGMA cipher_page_size = 4096;\nPRAGMA kdf_iter = 256000;",
+        escaped
+    ))
+}
+
 fn open_db_connection() -> Result<Connection, rusqlite::Error> {
     let path = get_db_path();
 
@@ -43,26 +162,279 @@ fn open_db_connection() -> Result<Connection, rusqlite::Error> {
     }
 
     let conn = Connection::open(path)?;
-    conn.execute_batch("PRAGMA journal_mode=WAL;")?;
+
+    // 密钥必须在 journal_mode 等任何其它 PRAGMA / schema 语句之前应用。
+    if let Some(passphrase) = current_passphrase() {
+        apply_key(&conn, &passphrase)?;
+    }
+
+    tune_connection(&conn)?;
     Ok(conn)
 }
 
-fn get_db_connection() -> Result<&'static Mutex<Connection>, rusqlite::Error> {
-    if let Some(conn) = DB_CONNECTION.get() {
-        return Ok(conn);
+/// 为池中每条连接套用统一的性能/一致性 PRAGMA。
+///
+/// WAL 让读写互不阻塞；`synchronous=NORMAL` 在 WAL 下兼顾安全与吞吐；
+/// `busy_timeout` 使偶发的锁竞争自动重试而非立刻报 `SQLITE_BUSY`；
+/// `foreign_keys=ON` 打开外键约束（各连接独立，必须逐条设置）。
+fn tune_connection(conn: &Connection) -> Result<(), rusqlite::Error> {
+    let busy_timeout = BUSY_TIMEOUT_MS.load(Ordering::Relaxed);
+    conn.execute_batch(&format!(
+        "PRAGMA journal_mode=WAL;\n\
+         PRAGMA synchronous=NORMAL;\n\
+         PRAGMA busy_timeout={};\n\
+         PRAGMA foreign_keys=ON;",
+        busy_timeout
+    ))
+}
+
+fn now_ms() -> u64 {
+    use std::time::{SystemTime, UNIX_EPOCH};
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// 生成 `salt:hash` 形式的口令验证串（SHA-256(salt || passphrase)）。
+fn build_verifier(passphrase: &str) -> String {
+    use sha2::{Digest, Sha256};
+    // 以当前时间派生一个一次性盐，避免相同口令产生相同验证串。
+    let salt = format!("{:x}", now_ms());
+    let mut hasher = Sha256::new();
+    hasher.update(salt.as_bytes());
+    hasher.update(passphrase.as_bytes());
+    format!("{}:{:x}", salt, hasher.finalize())
+}
+
+fn write_verifier(passphrase: &str) -> Result<(), String> {
+    std::fs::write(security_file_path(), build_verifier(passphrase))
+        .map_err(|e| format!("failed to persist passphrase verifier: {}", e))
+}
+
+/// 用磁盘上的 `salt:hash` 校验口令是否与上次设定的一致。
+///
+/// 验证串缺失（从未设定口令）返回 `None`；存在时按 `SHA-256(salt || passphrase)`
+/// 重算并比对，返回 `Some(bool)`。据此可在打开连接前就区分“未设口令 / 口令正确 /
+/// 口令错误”三种情形，给出更明确的错误。
+fn verify_passphrase(passphrase: &str) -> Option<bool> {
+    use sha2::{Digest, Sha256};
+    let stored = std::fs::read_to_string(security_file_path()).ok()?;
+    let (salt, hash) = stored.trim().split_once(':')?;
+    let mut hasher = Sha256::new();
+    hasher.update(salt.as_bytes());
+    hasher.update(passphrase.as_bytes());
+    Some(format!("{:x}", hasher.finalize()) == hash)
+}
+
+/// 设置主口令：写入验证盐/哈希并登记到内存，使后续连接自动加密。
+///
+/// 必须在 [`init_database`] 之前调用，因为密钥要在首次 `Connection::open` 后、
+/// 任何 schema 语句之前生效。绝不会把口令本身写入磁盘。
+pub fn set_master_passphrase(passphrase: &str) -> Result<(), String> {
+    if passphrase.is_empty() {
+        return Err("passphrase cannot be empty".to_string());
+    }
+    write_verifier(passphrase)?;
+    if let Ok(mut slot) = passphrase_slot().lock() {
+        *slot = Some(passphrase.to_string());
+    }
+    Ok(())
+}
+
+/// 启动时从环境变量 `SNAPPASTE_PASSPHRASE` 读取主口令并登记密钥（若已配置）。
+///
+/// 必须在 [`init_database`] 之前调用：仅把口令写入内存槽位，使首个连接的
+/// `apply_key` 生效，既不触碰磁盘上的验证串，也不重写盐值。未设置该变量时为空操作。
+pub fn unlock_from_env() {
+    if let Ok(passphrase) = std::env::var("SNAPPASTE_PASSPHRASE") {
+        if passphrase.is_empty() {
+            return;
+        }
+        // 若已存在验证串而口令不符，保持锁定而非用错误密钥打开一个无法解密的库。
+        if let Some(false) = verify_passphrase(&passphrase) {
+            eprintln!(
+                "[WARN] SNAPPASTE_PASSPHRASE does not match the stored verifier; leaving database locked"
+            );
+            return;
+        }
+        if let Ok(mut slot) = passphrase_slot().lock() {
+            *slot = Some(passphrase);
+        }
+    }
+}
+
+/// 用口令解锁数据库：登记密钥并通过一次 `sqlite_master` 访问验证其正确性。
+///
+/// 口令错误时 SQLCipher 会在该查询处报错，这里据此返回一个明确区分的错误，
+/// 而不是静默失败。
+pub fn unlock(passphrase: &str) -> Result<(), String> {
+    // 先用磁盘验证串快速判定：口令与上次设定不符时直接报错，避免无谓打开连接。
+    if let Some(false) = verify_passphrase(passphrase) {
+        return Err("invalid passphrase: does not match the stored verifier".to_string());
+    }
+
+    if let Ok(mut slot) = passphrase_slot().lock() {
+        *slot = Some(passphrase.to_string());
+    }
+
+    let conn = lock_db_connection().map_err(|e| e.to_string())?;
+    conn.query_row("SELECT count(*) FROM sqlite_master", [], |_| Ok(()))
+        .map_err(|_| "invalid passphrase: database could not be decrypted".to_string())?;
+    drop(conn);
+    // 注册密钥前打开的读连接（未加密/无密钥）此刻已失效，逐出重建。
+    clear_idle_readers();
+    Ok(())
+}
+
+/// 原地更换密钥（`PRAGMA rekey`），并刷新验证串。
+pub fn rekey(new_passphrase: &str) -> Result<(), String> {
+    if new_passphrase.is_empty() {
+        return Err("passphrase cannot be empty".to_string());
+    }
+    let conn = lock_db_connection().map_err(|e| e.to_string())?;
+    let escaped = new_passphrase.replace('\'', "''");
+    conn.execute_batch(&format!("PRAGMA rekey = '{}';", escaped))
+        .map_err(|e| format!("failed to rekey database: {}", e))?;
+    drop(conn);
+    clear_idle_readers();
+
+    write_verifier(new_passphrase)?;
+    if let Ok(mut slot) = passphrase_slot().lock() {
+        *slot = Some(new_passphrase.to_string());
+    }
+    Ok(())
+}
+
+/// 把现有明文数据库迁移到一个用口令加密的新库。
+///
+/// 通过 `ATTACH` 一个带 `KEY` 的目标库并调用 `sqlcipher_export` 整体导出，再
+/// `DETACH`。调用方随后可用加密库替换原文件，并 [`set_master_passphrase`]。
+pub fn migrate_plaintext_to_encrypted(
+    encrypted_path: &std::path::Path,
+    passphrase: &str,
+) -> Result<(), String> {
+    if passphrase.is_empty() {
+        return Err("passphrase cannot be empty".to_string());
+    }
+    let conn = lock_db_connection().map_err(|e| e.to_string())?;
+    let target = encrypted_path.to_string_lossy().replace('\'', "''");
+    let escaped_key = passphrase.replace('\'', "''");
+    conn.execute_batch(&format!(
+        "ATTACH DATABASE '{}' AS encrypted KEY '{}';
+         SELECT sqlcipher_export('encrypted');
+         DETACH DATABASE encrypted;",
+        target, escaped_key
+    ))
+    .map_err(|e| format!("failed to export encrypted database: {}", e))?;
+    drop(conn);
+    clear_idle_readers();
+    Ok(())
+}
+
+/// 单写多读的连接池。
+///
+/// 仅保留一条写连接（变更钩子与 `last_insert_rowid()` 都依附其上），用互斥锁串行
+/// 化写入以规避并发写的 `SQLITE_BUSY`；读连接按需创建并归还到一个上限为
+/// [`MAX_READERS`] 的空闲表，让检索在 WAL 下与写入并行，消除读写间的队头阻塞。
+struct ConnectionPool {
+    writer: Mutex<Connection>,
+    idle_readers: Mutex<Vec<Connection>>,
+}
+
+static POOL: OnceLock<ConnectionPool> = OnceLock::new();
+
+fn get_pool() -> Result<&'static ConnectionPool, rusqlite::Error> {
+    if let Some(pool) = POOL.get() {
+        return Ok(pool);
     }
 
-    let conn = open_db_connection()?;
-    let _ = DB_CONNECTION.set(Mutex::new(conn));
-    DB_CONNECTION.get().ok_or_else(|| {
-        rusqlite::Error::InvalidParameterName("failed to initialize database singleton".to_string())
+    let writer = open_db_connection()?;
+    install_change_hooks(&writer);
+    let _ = POOL.set(ConnectionPool {
+        writer: Mutex::new(writer),
+        idle_readers: Mutex::new(Vec::new()),
+    });
+    POOL.get().ok_or_else(|| {
+        rusqlite::Error::InvalidParameterName("failed to initialize connection pool".to_string())
     })
 }
 
+/// 取得写连接的独占句柄；所有写入及依赖 `last_insert_rowid()` 的操作都走这里。
 fn lock_db_connection() -> Result<MutexGuard<'static, Connection>, rusqlite::Error> {
-    let conn = get_db_connection()?;
-    conn.lock()
-        .map_err(|_| rusqlite::Error::InvalidParameterName("database mutex poisoned".to_string()))
+    let pool = get_pool()?;
+    pool.writer
+        .lock()
+        .map_err(|_| rusqlite::Error::InvalidParameterName("database writer poisoned".to_string()))
+}
+
+/// 从池中借出一条只读连接；[`PooledReader`] 析构时把连接归还空闲表。
+fn reader() -> Result<PooledReader, rusqlite::Error> {
+    let pool = get_pool()?;
+    let conn = {
+        let mut idle = pool
+            .idle_readers
+            .lock()
+            .map_err(|_| rusqlite::Error::InvalidParameterName("reader pool poisoned".to_string()))?;
+        idle.pop()
+    };
+    let conn = match conn {
+        Some(conn) => conn,
+        None => open_db_connection()?,
+    };
+    Ok(PooledReader { conn: Some(conn) })
+}
+
+/// 借出的只读连接：像 `&Connection` 一样使用，析构时把底层连接还给空闲表
+/// （空闲表已满则直接关闭，保证常驻读连接数不超过 [`MAX_READERS`]）。
+struct PooledReader {
+    conn: Option<Connection>,
+}
+
+impl Deref for PooledReader {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        self.conn
+            .as_ref()
+            .expect("pooled reader used after drop")
+    }
+}
+
+impl Drop for PooledReader {
+    fn drop(&mut self) {
+        let Some(conn) = self.conn.take() else {
+            return;
+        };
+        if let Some(pool) = POOL.get() {
+            if let Ok(mut idle) = pool.idle_readers.lock() {
+                if idle.len() < MAX_READERS {
+                    idle.push(conn);
+                }
+            }
+        }
+    }
+}
+
+/// 丢弃全部空闲读连接，迫使后续读取以最新的密钥/超时重新建立连接。
+///
+/// 任何改动写连接密钥或整体覆盖数据库文件的操作（改密钥、还原备份、加密迁移）
+/// 都必须调用它，否则缓存的读连接会继续用旧密钥/旧快照服务查询。
+fn clear_idle_readers() {
+    if let Some(pool) = POOL.get() {
+        if let Ok(mut idle) = pool.idle_readers.lock() {
+            idle.clear();
+        }
+    }
+}
+
+/// 更新 `busy_timeout` 并立即应用到写连接；新建的读连接会读取最新值。
+fn configure_busy_timeout(ms: u64) {
+    BUSY_TIMEOUT_MS.store(ms, Ordering::Relaxed);
+    if let Ok(conn) = lock_db_connection() {
+        let _ = conn.busy_timeout(std::time::Duration::from_millis(ms));
+    }
+    clear_idle_readers();
 }
 
 fn ensure_settings_columns(conn: &Connection) -> Result<(), rusqlite::Error> {
@@ -87,6 +459,13 @@ fn ensure_settings_columns(conn: &Connection) -> Result<(), rusqlite::Error> {
         )?;
     }
 
+    if !columns.iter().any(|c| c == "overlay_mode") {
+        conn.execute(
+            "ALTER TABLE settings ADD COLUMN overlay_mode INTEGER DEFAULT 1",
+            [],
+        )?;
+    }
+
     if !columns.iter().any(|c| c == "window_width") {
         conn.execute("ALTER TABLE settings ADD COLUMN window_width INTEGER", [])?;
     }
@@ -95,6 +474,49 @@ fn ensure_settings_columns(conn: &Connection) -> Result<(), rusqlite::Error> {
         conn.execute("ALTER TABLE settings ADD COLUMN window_height INTEGER", [])?;
     }
 
+    if !columns.iter().any(|c| c == "pinned") {
+        conn.execute("ALTER TABLE settings ADD COLUMN pinned INTEGER DEFAULT 0", [])?;
+    }
+
+    // 置顶面板的停放位置/大小单独持久化，与普通弹窗尺寸互不影响。
+    for column in ["pinned_x", "pinned_y", "pinned_width", "pinned_height"] {
+        if !columns.iter().any(|c| c == column) {
+            conn.execute(
+                &format!("ALTER TABLE settings ADD COLUMN {} INTEGER", column),
+                [],
+            )?;
+        }
+    }
+
+    if !columns.iter().any(|c| c == "enable_image_recording") {
+        conn.execute(
+            "ALTER TABLE settings ADD COLUMN enable_image_recording INTEGER DEFAULT 0",
+            [],
+        )?;
+    }
+
+    if !columns.iter().any(|c| c == "busy_timeout_ms") {
+        conn.execute(
+            "ALTER TABLE settings ADD COLUMN busy_timeout_ms INTEGER DEFAULT 5000",
+            [],
+        )?;
+    }
+
+    // 其余可绑定动作各自的全局快捷键，默认空串表示未绑定。
+    for column in [
+        "hotkey_paste_plain_text",
+        "hotkey_clear_history",
+        "hotkey_toggle_pin",
+        "hotkey_capture_selection",
+    ] {
+        if !columns.iter().any(|c| c == column) {
+            conn.execute(
+                &format!("ALTER TABLE settings ADD COLUMN {} TEXT DEFAULT ''", column),
+                [],
+            )?;
+        }
+    }
+
     Ok(())
 }
 
@@ -118,6 +540,18 @@ fn ensure_history_columns(conn: &Connection) -> Result<(), rusqlite::Error> {
             [],
         )?;
     }
+    if !columns.iter().any(|c| c == "html_data") {
+        conn.execute(
+            "ALTER TABLE clipboard_history ADD COLUMN html_data TEXT",
+            [],
+        )?;
+    }
+    if !columns.iter().any(|c| c == "bundle_data") {
+        conn.execute(
+            "ALTER TABLE clipboard_history ADD COLUMN bundle_data BLOB",
+            [],
+        )?;
+    }
 
     Ok(())
 }
@@ -131,10 +565,18 @@ fn sanitize_settings(settings: &Settings) -> Settings {
         } else {
             settings.hotkey.trim().to_string()
         },
+        hotkey_paste_plain_text: settings.hotkey_paste_plain_text.trim().to_string(),
+        hotkey_clear_history: settings.hotkey_clear_history.trim().to_string(),
+        hotkey_toggle_pin: settings.hotkey_toggle_pin.trim().to_string(),
+        hotkey_capture_selection: settings.hotkey_capture_selection.trim().to_string(),
         theme: settings.theme.clone(),
         keep_days: settings.keep_days.max(0),
         max_records: settings.max_records.max(0),
         auto_start: settings.auto_start,
+        overlay_mode: settings.overlay_mode,
+        pinned: settings.pinned,
+        enable_image_recording: settings.enable_image_recording,
+        busy_timeout_ms: settings.busy_timeout_ms.clamp(100, 60_000),
     }
 }
 
@@ -147,7 +589,15 @@ fn get_settings_from_conn(conn: &Connection) -> Result<Settings, rusqlite::Error
             theme,
             keep_days,
             max_records,
-            auto_start
+            auto_start,
+            COALESCE(overlay_mode, 1) as overlay_mode,
+            COALESCE(pinned, 0) as pinned,
+            COALESCE(enable_image_recording, 0) as enable_image_recording,
+            COALESCE(busy_timeout_ms, 5000) as busy_timeout_ms,
+            COALESCE(hotkey_paste_plain_text, '') as hotkey_paste_plain_text,
+            COALESCE(hotkey_clear_history, '') as hotkey_clear_history,
+            COALESCE(hotkey_toggle_pin, '') as hotkey_toggle_pin,
+            COALESCE(hotkey_capture_selection, '') as hotkey_capture_selection
          FROM settings WHERE id = 1",
     )?;
     let mut rows = stmt.query([])?;
@@ -161,6 +611,14 @@ fn get_settings_from_conn(conn: &Connection) -> Result<Settings, rusqlite::Error
             keep_days: row.get(4)?,
             max_records: row.get(5)?,
             auto_start: row.get::<_, i32>(6)? > 0,
+            overlay_mode: row.get::<_, i32>(7)? > 0,
+            pinned: row.get::<_, i32>(8)? > 0,
+            enable_image_recording: row.get::<_, i32>(9)? > 0,
+            busy_timeout_ms: row.get::<_, i64>(10)?.max(0) as u64,
+            hotkey_paste_plain_text: row.get(11)?,
+            hotkey_clear_history: row.get(12)?,
+            hotkey_toggle_pin: row.get(13)?,
+            hotkey_capture_selection: row.get(14)?,
         })
     } else {
         Ok(Settings::default())
@@ -215,6 +673,8 @@ pub fn init_database() -> Result<()> {
             id INTEGER PRIMARY KEY AUTOINCREMENT,
             content_type TEXT NOT NULL,
             content TEXT,
+            html_data TEXT,
+            bundle_data BLOB,
             image_data BLOB,
             is_favorite INTEGER DEFAULT 0,
             is_pinned INTEGER DEFAULT 0,
@@ -235,8 +695,20 @@ pub fn init_database() -> Result<()> {
             keep_days INTEGER DEFAULT 1,
             max_records INTEGER DEFAULT 500,
             auto_start INTEGER DEFAULT 0,
+            overlay_mode INTEGER DEFAULT 1,
             window_width INTEGER,
-            window_height INTEGER
+            window_height INTEGER,
+            pinned INTEGER DEFAULT 0,
+            pinned_x INTEGER,
+            pinned_y INTEGER,
+            pinned_width INTEGER,
+            pinned_height INTEGER,
+            enable_image_recording INTEGER DEFAULT 0,
+            busy_timeout_ms INTEGER DEFAULT 5000,
+            hotkey_paste_plain_text TEXT DEFAULT '',
+            hotkey_clear_history TEXT DEFAULT '',
+            hotkey_toggle_pin TEXT DEFAULT '',
+            hotkey_capture_selection TEXT DEFAULT ''
         )",
         [],
     )?;
@@ -247,45 +719,90 @@ pub fn init_database() -> Result<()> {
         [],
     )?;
 
+    // 创建 / 回填 FTS5 全文索引
+    let fts_present: bool = conn
+        .query_row(
+            "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = 'clipboard_history_fts'",
+            [],
+            |row| row.get::<_, i32>(0),
+        )
+        .map(|count| count > 0)
+        .unwrap_or(false);
+
+    // 无内容列的外部内容表，镜像 clipboard_history.content；
+    // 删除/更新之后由触发器发出标准的 'delete' 指令，更新时再写入新内容。
+    conn.execute_batch(
+        "CREATE VIRTUAL TABLE IF NOT EXISTS clipboard_history_fts
+            USING fts5(content, content='clipboard_history', content_rowid='id');
+         CREATE TRIGGER IF NOT EXISTS clipboard_history_fts_ai
+            AFTER INSERT ON clipboard_history BEGIN
+                INSERT INTO clipboard_history_fts(rowid, content)
+                VALUES (new.id, COALESCE(new.content, ''));
+            END;
+         CREATE TRIGGER IF NOT EXISTS clipboard_history_fts_ad
+            AFTER DELETE ON clipboard_history BEGIN
+                INSERT INTO clipboard_history_fts(clipboard_history_fts, rowid, content)
+                VALUES ('delete', old.id, COALESCE(old.content, ''));
+            END;
+         CREATE TRIGGER IF NOT EXISTS clipboard_history_fts_au
+            AFTER UPDATE ON clipboard_history BEGIN
+                INSERT INTO clipboard_history_fts(clipboard_history_fts, rowid, content)
+                VALUES ('delete', old.id, COALESCE(old.content, ''));
+                INSERT INTO clipboard_history_fts(rowid, content)
+                VALUES (new.id, COALESCE(new.content, ''));
+            END;",
+    )?;
+
+    // 老库首次建立 FTS 表时回填已有记录。
+    if !fts_present {
+        conn.execute(
+            "INSERT INTO clipboard_history_fts(rowid, content)
+             SELECT id, COALESCE(content, '') FROM clipboard_history",
+            [],
+        )?;
+    }
+
     // 初始化默认设置
     conn.execute("INSERT OR IGNORE INTO settings (id) VALUES (1)", [])?;
 
     ensure_settings_columns(&conn)?;
     ensure_history_columns(&conn)?;
 
+    // 用持久化的设置校准连接池的 busy_timeout。
+    if let Ok(settings) = get_settings_from_conn(&conn) {
+        BUSY_TIMEOUT_MS.store(settings.busy_timeout_ms.clamp(100, 60_000), Ordering::Relaxed);
+        let _ = conn.busy_timeout(std::time::Duration::from_millis(
+            BUSY_TIMEOUT_MS.load(Ordering::Relaxed),
+        ));
+    }
+
     Ok(())
 }
 
 pub fn get_history(limit: i32, offset: i32) -> Result<Vec<ClipboardRecord>, rusqlite::Error> {
-    let conn = lock_db_connection()?;
-
-    let mut stmt = conn.prepare(
-        "SELECT id, content_type, COALESCE(content, '') as content,
-                NULL as image_data, COALESCE(is_favorite, 0) as is_favorite,
-                COALESCE(is_pinned, 0) as is_pinned,
-                COALESCE(source_app, '') as source_app,
-                created_at
-         FROM clipboard_history
-         ORDER BY COALESCE(is_pinned, 0) DESC, created_at DESC
-         LIMIT ?1 OFFSET ?2",
-    )?;
+    query_history(&HistoryFilter::default(), limit, offset)
+}
 
-    let records = stmt
-        .query_map(params![limit, offset], |row| {
-            Ok(ClipboardRecord {
-                id: row.get(0)?,
-                content_type: row.get(1)?,
-                content: row.get(2)?,
-                image_data: row.get(3)?,
-                is_favorite: row.get::<_, i32>(4)? > 0,
-                is_pinned: row.get::<_, i32>(5)? > 0,
-                source_app: row.get(6)?,
-                created_at: row.get(7)?,
-            })
-        })?
-        .collect::<std::result::Result<Vec<_>, _>>()?;
+/// 把标准的 `id, content_type, content, image_data, is_favorite, is_pinned,
+/// source_app, created_at, html, bundle` 查询结果行映射为 [`ClipboardRecord`]。
+fn map_record_row(row: &rusqlite::Row<'_>) -> Result<ClipboardRecord, rusqlite::Error> {
+    Ok(ClipboardRecord {
+        id: row.get(0)?,
+        content_type: row.get(1)?,
+        content: row.get(2)?,
+        html: row.get(8)?,
+        bundle: decode_bundle(row.get::<_, Option<Vec<u8>>>(9)?),
+        image_data: row.get(3)?,
+        is_favorite: row.get::<_, i32>(4)? > 0,
+        is_pinned: row.get::<_, i32>(5)? > 0,
+        source_app: row.get(6)?,
+        created_at: row.get(7)?,
+    })
+}
 
-    Ok(records)
+/// 把 `bundle_data` 列的 JSON 字节还原成格式集合；缺失或损坏时当作无 bundle。
+fn decode_bundle(blob: Option<Vec<u8>>) -> Option<Vec<crate::models::ClipboardFormatBlob>> {
+    blob.as_deref().and_then(|b| serde_json::from_slice(b).ok())
 }
 
 fn escape_like_pattern(keyword: &str) -> String {
@@ -301,103 +818,277 @@ fn escape_like_pattern(keyword: &str) -> String {
     escaped
 }
 
-pub fn search_history(keyword: &str, limit: i32) -> Result<Vec<ClipboardRecord>, rusqlite::Error> {
-    let conn = lock_db_connection()?;
-    let pattern = escape_like_pattern(keyword);
+/// 把用户输入构造成一条安全的 FTS5 `MATCH` 查询串。
+///
+/// 为避免 MATCH 语法错误，每个词都用双引号包裹（内部的双引号按 FTS 规则翻倍
+/// 转义）。`Prefix` 在每个词后追加 `*`；`Fuzzy` 把关键字拆成单字、每字都做前缀
+/// 匹配以放宽命中；`FullText` 直接对分词做隐式 AND。
+fn build_fts_match(keyword: &str, mode: SearchMode) -> String {
+    fn quote(token: &str) -> String {
+        format!("\"{}\"", token.replace('"', "\"\""))
+    }
 
-    let mut stmt = conn.prepare(
-        "SELECT id, content_type, COALESCE(content, '') as content,
-                NULL as image_data, COALESCE(is_favorite, 0) as is_favorite,
-                COALESCE(is_pinned, 0) as is_pinned,
-                COALESCE(source_app, '') as source_app,
-                created_at
-         FROM clipboard_history
-         WHERE content LIKE ?1 ESCAPE '\'
-         ORDER BY COALESCE(is_pinned, 0) DESC, created_at DESC
-         LIMIT ?2",
-    )?;
+    match mode {
+        SearchMode::Fuzzy => keyword
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .map(|c| format!("{}*", quote(&c.to_string())))
+            .collect::<Vec<_>>()
+            .join(" "),
+        SearchMode::Prefix => keyword
+            .split_whitespace()
+            .map(|token| format!("{}*", quote(token)))
+            .collect::<Vec<_>>()
+            .join(" "),
+        // FullText 与 Exact(此处不会走到) 均按分词做隐式 AND。
+        _ => keyword
+            .split_whitespace()
+            .map(quote)
+            .collect::<Vec<_>>()
+            .join(" "),
+    }
+}
 
+/// 按 [`HistoryFilter`] 动态拼装 WHERE 子句后检索历史记录。
+///
+/// 各谓词按需追加，对应的绑定值依次压入 `Vec<Box<dyn ToSql>>`，使取值始终保持
+/// 参数化；无论叠加多少过滤条件，排序尾部恒为 `is_pinned DESC, created_at DESC`。
+/// 关键字在 [`SearchMode::Exact`] 下走转义 `LIKE`，其余模式 `JOIN` FTS5 虚表做
+/// `MATCH`。本函数是历史检索的唯一实现，其余查询均为其薄封装。
+pub fn query_history(
+    filter: &HistoryFilter,
+    limit: i32,
+    offset: i32,
+) -> Result<Vec<ClipboardRecord>, rusqlite::Error> {
+    let conn = reader()?;
+
+    let mut predicates: Vec<String> = Vec::new();
+    let mut binds: Vec<Box<dyn ToSql>> = Vec::new();
+    let mut join = "";
+    let mut fts = false;
+
+    let keyword = filter
+        .keyword
+        .as_deref()
+        .map(str::trim)
+        .filter(|k| !k.is_empty());
+    if let Some(keyword) = keyword {
+        match filter.mode {
+            SearchMode::Exact => {
+                predicates.push("h.content LIKE ? ESCAPE '\\'".to_string());
+                binds.push(Box::new(escape_like_pattern(keyword)));
+            }
+            mode => {
+                join = " JOIN clipboard_history_fts fts ON fts.rowid = h.id";
+                predicates.push("clipboard_history_fts MATCH ?".to_string());
+                binds.push(Box::new(build_fts_match(keyword, mode)));
+                fts = true;
+            }
+        }
+    }
+
+    if let Some(content_type) = filter
+        .content_type
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+    {
+        predicates.push("h.content_type = ?".to_string());
+        binds.push(Box::new(content_type.to_string()));
+    }
+    if let Some(source_app) = filter
+        .source_app
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+    {
+        predicates.push("COALESCE(h.source_app, '') = ?".to_string());
+        binds.push(Box::new(source_app.to_string()));
+    }
+    if let Some(after) = filter.after.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
+        predicates.push("h.created_at >= ?".to_string());
+        binds.push(Box::new(after.to_string()));
+    }
+    if let Some(before) = filter
+        .before
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+    {
+        predicates.push("h.created_at <= ?".to_string());
+        binds.push(Box::new(before.to_string()));
+    }
+    if filter.favorites_only {
+        predicates.push("COALESCE(h.is_favorite, 0) = 1".to_string());
+    }
+    if filter.pinned_only {
+        predicates.push("COALESCE(h.is_pinned, 0) = 1".to_string());
+    }
+
+    let where_clause = if predicates.is_empty() {
+        String::new()
+    } else {
+        format!("WHERE {}", predicates.join(" AND "))
+    };
+
+    // FTS 模式下按 bm25() 相关性排序：先置顶/收藏，再按 FTS 相关度（bm25 值越小越
+    // 相关），最后回落到时间倒序；非 FTS 模式无相关度可言，仍按置顶与时间排序。
+    let order_by = if fts {
+        "ORDER BY COALESCE(h.is_pinned, 0) DESC, COALESCE(h.is_favorite, 0) DESC, bm25(clipboard_history_fts), h.created_at DESC"
+    } else {
+        "ORDER BY COALESCE(h.is_pinned, 0) DESC, h.created_at DESC"
+    };
+
+    let sql = format!(
+        "SELECT h.id, h.content_type, COALESCE(h.content, '') as content,
+                NULL as image_data, COALESCE(h.is_favorite, 0) as is_favorite,
+                COALESCE(h.is_pinned, 0) as is_pinned,
+                COALESCE(h.source_app, '') as source_app,
+                h.created_at,
+                NULL as html,
+                NULL as bundle
+         FROM clipboard_history h{join}
+         {where_clause}
+         {order_by}
+         LIMIT ? OFFSET ?"
+    );
+
+    binds.push(Box::new(limit));
+    binds.push(Box::new(offset));
+
+    let mut stmt = conn.prepare(&sql)?;
     let records = stmt
-        .query_map(params![pattern, limit], |row| {
-            Ok(ClipboardRecord {
-                id: row.get(0)?,
-                content_type: row.get(1)?,
-                content: row.get(2)?,
-                image_data: row.get(3)?,
-                is_favorite: row.get::<_, i32>(4)? > 0,
-                is_pinned: row.get::<_, i32>(5)? > 0,
-                source_app: row.get(6)?,
-                created_at: row.get(7)?,
-            })
-        })?
+        .query_map(
+            rusqlite::params_from_iter(binds.iter().map(|b| b.as_ref())),
+            map_record_row,
+        )?
         .collect::<std::result::Result<Vec<_>, _>>()?;
-
     Ok(records)
 }
 
+pub fn search_history(
+    keyword: &str,
+    mode: SearchMode,
+    limit: i32,
+) -> Result<Vec<ClipboardRecord>, rusqlite::Error> {
+    query_history(
+        &HistoryFilter {
+            keyword: Some(keyword.to_string()),
+            mode,
+            ..Default::default()
+        },
+        limit,
+        0,
+    )
+}
+
 pub fn get_favorite_history(
     limit: i32,
     offset: i32,
 ) -> Result<Vec<ClipboardRecord>, rusqlite::Error> {
-    let conn = lock_db_connection()?;
+    query_history(
+        &HistoryFilter {
+            favorites_only: true,
+            ..Default::default()
+        },
+        limit,
+        offset,
+    )
+}
+
+/// 读取全部历史记录（含 `image_data`），供备份/导出使用。
+pub fn get_all_records() -> Result<Vec<ClipboardRecord>, rusqlite::Error> {
+    let conn = reader()?;
 
     let mut stmt = conn.prepare(
         "SELECT id, content_type, COALESCE(content, '') as content,
-                NULL as image_data, COALESCE(is_favorite, 0) as is_favorite,
+                image_data, COALESCE(is_favorite, 0) as is_favorite,
                 COALESCE(is_pinned, 0) as is_pinned,
                 COALESCE(source_app, '') as source_app,
-                created_at
+                created_at,
+                html_data as html,
+                bundle_data as bundle
          FROM clipboard_history
-         WHERE COALESCE(is_favorite, 0) = 1
-         ORDER BY COALESCE(is_pinned, 0) DESC, created_at DESC
-         LIMIT ?1 OFFSET ?2",
+         ORDER BY COALESCE(is_pinned, 0) DESC, created_at DESC",
     )?;
 
     let records = stmt
-        .query_map(params![limit, offset], |row| {
-            Ok(ClipboardRecord {
-                id: row.get(0)?,
-                content_type: row.get(1)?,
-                content: row.get(2)?,
-                image_data: row.get(3)?,
-                is_favorite: row.get::<_, i32>(4)? > 0,
-                is_pinned: row.get::<_, i32>(5)? > 0,
-                source_app: row.get(6)?,
-                created_at: row.get(7)?,
-            })
-        })?
+        .query_map([], map_record_row)?
         .collect::<std::result::Result<Vec<_>, _>>()?;
 
     Ok(records)
 }
 
+/// 使用 SQLite 在线备份 API 将当前库整体复制到 `path`。
+///
+/// 分批拷贝页面，应用在此期间可继续读写，无需停机；目标文件若已存在会被覆盖。
+pub fn backup_to(path: &std::path::Path) -> Result<(), String> {
+    // 以读连接为源做在线备份，写连接不被占用；源库在备份途中被写入时，SQLite 会在
+    // 下一步自动从头重拷。
+    let src = reader().map_err(|e| e.to_string())?;
+    let mut dst = Connection::open(path).map_err(|e| e.to_string())?;
+
+    let backup =
+        rusqlite::backup::Backup::new(&src, &mut dst).map_err(|e| e.to_string())?;
+    backup
+        .run_to_completion(64, std::time::Duration::from_millis(10), None)
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// 从 `path` 处的备份还原，覆盖当前库的页面。
+///
+/// 在换入之前先校验来源库的 schema（必须包含 `clipboard_history` 表），避免把
+/// 无关或损坏的文件误当成备份恢复。
+pub fn restore_from(path: &std::path::Path) -> Result<(), String> {
+    let source = Connection::open_with_flags(
+        path,
+        rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY,
+    )
+    .map_err(|e| e.to_string())?;
+
+    let schema_ok: bool = source
+        .query_row(
+            "SELECT count(*) FROM sqlite_master
+             WHERE type = 'table' AND name = 'clipboard_history'",
+            [],
+            |row| row.get::<_, i32>(0),
+        )
+        .map(|count| count > 0)
+        .unwrap_or(false);
+    if !schema_ok {
+        return Err("backup is missing the clipboard_history schema".to_string());
+    }
+
+    let mut dst = lock_db_connection().map_err(|e| e.to_string())?;
+    let backup =
+        rusqlite::backup::Backup::new(&source, &mut dst).map_err(|e| e.to_string())?;
+    backup
+        .run_to_completion(64, std::time::Duration::from_millis(10), None)
+        .map_err(|e| e.to_string())?;
+    drop(dst);
+    clear_idle_readers();
+    Ok(())
+}
+
 pub fn get_all_favorite_history() -> Result<Vec<ClipboardRecord>, rusqlite::Error> {
-    let conn = lock_db_connection()?;
+    let conn = reader()?;
 
     let mut stmt = conn.prepare(
         "SELECT id, content_type, COALESCE(content, '') as content,
-                NULL as image_data, COALESCE(is_favorite, 0) as is_favorite,
+                image_data, COALESCE(is_favorite, 0) as is_favorite,
                 COALESCE(is_pinned, 0) as is_pinned,
                 COALESCE(source_app, '') as source_app,
-                created_at
+                created_at,
+                html_data as html,
+                bundle_data as bundle
          FROM clipboard_history
          WHERE COALESCE(is_favorite, 0) = 1
          ORDER BY COALESCE(is_pinned, 0) DESC, created_at DESC",
     )?;
 
     let records = stmt
-        .query_map([], |row| {
-            Ok(ClipboardRecord {
-                id: row.get(0)?,
-                content_type: row.get(1)?,
-                content: row.get(2)?,
-                image_data: row.get(3)?,
-                is_favorite: row.get::<_, i32>(4)? > 0,
-                is_pinned: row.get::<_, i32>(5)? > 0,
-                source_app: row.get(6)?,
-                created_at: row.get(7)?,
-            })
-        })?
+        .query_map([], map_record_row)?
         .collect::<std::result::Result<Vec<_>, _>>()?;
 
     Ok(records)
@@ -405,45 +1096,29 @@ pub fn get_all_favorite_history() -> Result<Vec<ClipboardRecord>, rusqlite::Erro
 
 pub fn search_favorite_history(
     keyword: &str,
+    mode: SearchMode,
     limit: i32,
 ) -> Result<Vec<ClipboardRecord>, rusqlite::Error> {
-    let conn = lock_db_connection()?;
-    let pattern = escape_like_pattern(keyword);
-
-    let mut stmt = conn.prepare(
-        "SELECT id, content_type, COALESCE(content, '') as content,
-                NULL as image_data, COALESCE(is_favorite, 0) as is_favorite,
-                COALESCE(is_pinned, 0) as is_pinned,
-                COALESCE(source_app, '') as source_app,
-                created_at
-         FROM clipboard_history
-         WHERE COALESCE(is_favorite, 0) = 1
-           AND content LIKE ?1 ESCAPE '\'
-         ORDER BY COALESCE(is_pinned, 0) DESC, created_at DESC
-         LIMIT ?2",
-    )?;
-
-    let records = stmt
-        .query_map(params![pattern, limit], |row| {
-            Ok(ClipboardRecord {
-                id: row.get(0)?,
-                content_type: row.get(1)?,
-                content: row.get(2)?,
-                image_data: row.get(3)?,
-                is_favorite: row.get::<_, i32>(4)? > 0,
-                is_pinned: row.get::<_, i32>(5)? > 0,
-                source_app: row.get(6)?,
-                created_at: row.get(7)?,
-            })
-        })?
-        .collect::<std::result::Result<Vec<_>, _>>()?;
-
-    Ok(records)
+    query_history(
+        &HistoryFilter {
+            keyword: Some(keyword.to_string()),
+            mode,
+            favorites_only: true,
+            ..Default::default()
+        },
+        limit,
+        0,
+    )
 }
 
 pub fn add_record(record: ClipboardRecord) -> Result<i64, rusqlite::Error> {
     let conn = lock_db_connection()?;
 
+    let bundle_blob = record
+        .bundle
+        .as_ref()
+        .and_then(|b| serde_json::to_vec(b).ok());
+
     // 文本类记录去重：命中则更新时间并复用原记录，保持列表简洁。
     let dedup_target = record.content_type != "image" && !record.content.trim().is_empty();
     if dedup_target {
@@ -479,13 +1154,17 @@ pub fn add_record(record: ClipboardRecord) -> Result<i64, rusqlite::Error> {
                  SET created_at = ?1,
                      source_app = ?2,
                      is_favorite = ?3,
-                     is_pinned = ?4
-                 WHERE id = ?5",
+                     is_pinned = ?4,
+                     html_data = ?5,
+                     bundle_data = ?6
+                 WHERE id = ?7",
                 params![
                     &record.created_at,
                     &record.source_app,
                     merged_favorite as i32,
                     merged_pinned as i32,
+                    &record.html,
+                    &bundle_blob,
                     keep_id
                 ],
             )?;
@@ -507,11 +1186,13 @@ pub fn add_record(record: ClipboardRecord) -> Result<i64, rusqlite::Error> {
     }
 
     conn.execute(
-        "INSERT INTO clipboard_history (content_type, content, image_data, is_favorite, is_pinned, source_app, created_at)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        "INSERT INTO clipboard_history (content_type, content, html_data, bundle_data, image_data, is_favorite, is_pinned, source_app, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
         params![
             record.content_type,
             record.content,
+            record.html,
+            bundle_blob,
             record.image_data,
             record.is_favorite as i32,
             record.is_pinned as i32,
@@ -528,13 +1209,15 @@ pub fn add_record(record: ClipboardRecord) -> Result<i64, rusqlite::Error> {
 }
 
 pub fn get_record_by_id(id: i64) -> Result<Option<ClipboardRecord>, rusqlite::Error> {
-    let conn = lock_db_connection()?;
+    let conn = reader()?;
     let mut stmt = conn.prepare(
         "SELECT id, content_type, COALESCE(content, '') as content,
                 image_data, COALESCE(is_favorite, 0) as is_favorite,
                 COALESCE(is_pinned, 0) as is_pinned,
                 COALESCE(source_app, '') as source_app,
-                created_at
+                created_at,
+                html_data as html,
+                bundle_data as bundle
          FROM clipboard_history
          WHERE id = ?1
          LIMIT 1",
@@ -546,6 +1229,8 @@ pub fn get_record_by_id(id: i64) -> Result<Option<ClipboardRecord>, rusqlite::Er
             id: row.get(0)?,
             content_type: row.get(1)?,
             content: row.get(2)?,
+            html: row.get(8)?,
+            bundle: decode_bundle(row.get::<_, Option<Vec<u8>>>(9)?),
             image_data: row.get(3)?,
             is_favorite: row.get::<_, i32>(4)? > 0,
             is_pinned: row.get::<_, i32>(5)? > 0,
@@ -597,7 +1282,7 @@ pub fn set_record_pinned(id: i64, pinned: bool) -> Result<(), rusqlite::Error> {
 }
 
 pub fn favorite_exists(content_type: &str, content: &str) -> Result<bool, rusqlite::Error> {
-    let conn = lock_db_connection()?;
+    let conn = reader()?;
     let exists: i32 = conn.query_row(
         "SELECT EXISTS(
             SELECT 1
@@ -614,7 +1299,7 @@ pub fn favorite_exists(content_type: &str, content: &str) -> Result<bool, rusqli
 }
 
 pub fn get_settings() -> Result<Settings, rusqlite::Error> {
-    let conn = lock_db_connection()?;
+    let conn = reader()?;
     get_settings_from_conn(&conn)
 }
 
@@ -630,7 +1315,15 @@ pub fn save_settings(settings: &Settings) -> Result<(), rusqlite::Error> {
             theme = ?4,
             keep_days = ?5,
             max_records = ?6,
-            auto_start = ?7
+            auto_start = ?7,
+            overlay_mode = ?8,
+            pinned = ?9,
+            enable_image_recording = ?10,
+            busy_timeout_ms = ?11,
+            hotkey_paste_plain_text = ?12,
+            hotkey_clear_history = ?13,
+            hotkey_toggle_pin = ?14,
+            hotkey_capture_selection = ?15
          WHERE id = 1",
         params![
             settings.hotkey_modifiers as i32,
@@ -640,10 +1333,22 @@ pub fn save_settings(settings: &Settings) -> Result<(), rusqlite::Error> {
             settings.keep_days,
             settings.max_records,
             settings.auto_start as i32,
+            settings.overlay_mode as i32,
+            settings.pinned as i32,
+            settings.enable_image_recording as i32,
+            settings.busy_timeout_ms as i64,
+            settings.hotkey_paste_plain_text,
+            settings.hotkey_clear_history,
+            settings.hotkey_toggle_pin,
+            settings.hotkey_capture_selection,
         ],
     )?;
 
     apply_retention_policy(&conn, &settings)?;
+    drop(conn);
+
+    // 写连接的锁已释放，再对池内所有连接下发新的 busy_timeout。
+    configure_busy_timeout(settings.busy_timeout_ms);
 
     Ok(())
 }
@@ -658,7 +1363,7 @@ pub fn save_window_size(width: i32, height: i32) -> Result<(), rusqlite::Error>
 }
 
 pub fn get_window_size() -> Result<Option<(i32, i32)>, rusqlite::Error> {
-    let conn = lock_db_connection()?;
+    let conn = reader()?;
     let mut stmt = conn.prepare("SELECT window_width, window_height FROM settings WHERE id = 1")?;
     let result = stmt.query_row([], |row| {
         let width: Option<i32> = row.get(0)?;
@@ -670,3 +1375,31 @@ pub fn get_window_size() -> Result<Option<(i32, i32)>, rusqlite::Error> {
         _ => Ok(None),
     }
 }
+
+/// 记下置顶面板当前的停放位置与大小（物理像素），与普通弹窗尺寸分开保存。
+pub fn save_pinned_geometry(x: i32, y: i32, width: i32, height: i32) -> Result<(), rusqlite::Error> {
+    let conn = lock_db_connection()?;
+    conn.execute(
+        "UPDATE settings SET pinned_x = ?1, pinned_y = ?2, pinned_width = ?3, pinned_height = ?4 WHERE id = 1",
+        params![x, y, width, height],
+    )?;
+    Ok(())
+}
+
+/// 读取上次停放的置顶面板几何信息；任一字段缺失时返回 `None`。
+pub fn get_pinned_geometry() -> Result<Option<(i32, i32, i32, i32)>, rusqlite::Error> {
+    let conn = reader()?;
+    let mut stmt =
+        conn.prepare("SELECT pinned_x, pinned_y, pinned_width, pinned_height FROM settings WHERE id = 1")?;
+    let result = stmt.query_row([], |row| {
+        let x: Option<i32> = row.get(0)?;
+        let y: Option<i32> = row.get(1)?;
+        let width: Option<i32> = row.get(2)?;
+        let height: Option<i32> = row.get(3)?;
+        Ok((x, y, width, height))
+    });
+    match result {
+        Ok((Some(x), Some(y), Some(w), Some(h))) => Ok(Some((x, y, w, h))),
+        _ => Ok(None),
+    }
+}
@@ -1,16 +1,50 @@
+use std::collections::HashMap;
 use std::sync::{Mutex, OnceLock};
 
-use tauri::{AppHandle, Runtime};
+use tauri::{AppHandle, Emitter, Runtime};
 use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut};
 
+use crate::models::Settings;
 use crate::tray;
 
 pub const DEFAULT_SHORTCUT: &str = "Ctrl+Shift+V";
 
-static REGISTERED_SHORTCUT: OnceLock<Mutex<Option<Shortcut>>> = OnceLock::new();
+/// 可绑定全局快捷键的动作。
+///
+/// 每个动作都可以在设置里绑定到不同的热键，`on_shortcut_triggered` 根据触发的
+/// `Shortcut` 查表分发到对应动作。
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ActionId {
+    /// 在光标附近呼出历史窗口（原先的唯一动作）。
+    ShowHistoryNearCursor,
+    /// 以纯文本形式粘贴最近一条记录。
+    PasteLastPlainText,
+    /// 清空非收藏历史。
+    ClearHistory,
+    /// 切换窗口置顶（pin）。
+    ToggleWindowPin,
+    /// 抓取当前焦点应用的选区并存入历史。
+    CaptureSelection,
+}
+
+impl ActionId {
+    /// 设置界面使用的稳定字符串标识。
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ActionId::ShowHistoryNearCursor => "show_history_near_cursor",
+            ActionId::PasteLastPlainText => "paste_last_plain_text",
+            ActionId::ClearHistory => "clear_history",
+            ActionId::ToggleWindowPin => "toggle_window_pin",
+            ActionId::CaptureSelection => "capture_selection",
+        }
+    }
+}
 
-fn hotkey_state() -> &'static Mutex<Option<Shortcut>> {
-    REGISTERED_SHORTCUT.get_or_init(|| Mutex::new(None))
+/// 已注册的动作 -> 快捷键映射。
+static REGISTERED_SHORTCUTS: OnceLock<Mutex<HashMap<ActionId, Shortcut>>> = OnceLock::new();
+
+fn registry() -> &'static Mutex<HashMap<ActionId, Shortcut>> {
+    REGISTERED_SHORTCUTS.get_or_init(|| Mutex::new(HashMap::new()))
 }
 
 fn parse_shortcut(value: &str) -> Result<Shortcut, String> {
@@ -20,44 +54,176 @@ fn parse_shortcut(value: &str) -> Result<Shortcut, String> {
         .map_err(|e| format!("invalid shortcut '{}': {}", value, e))
 }
 
-pub fn register_hotkey<R: Runtime>(app: &AppHandle<R>, value: &str) -> Result<String, String> {
-    let shortcut = parse_shortcut(value)?;
+/// 为某个动作注册（或重绑）全局快捷键。
+///
+/// 返回标准化后的快捷键字符串。解析失败时返回带动作标识的错误，便于设置界面
+/// 精确高亮是哪条绑定无效，而不是静默回退到默认值；当解析出的快捷键已被其它
+/// 动作占用时也会报告冲突。
+pub fn register_action_hotkey<R: Runtime>(
+    app: &AppHandle<R>,
+    action: ActionId,
+    value: &str,
+) -> Result<String, String> {
+    let shortcut = parse_shortcut(value)
+        .map_err(|e| format!("action '{}': {}", action.as_str(), e))?;
     let shortcut_string = shortcut.to_string();
     let manager = app.global_shortcut();
 
-    let mut state = hotkey_state()
+    let mut state = registry()
         .lock()
-        .map_err(|_| "failed to lock hotkey state".to_string())?;
-    if let Some(old) = state.take() {
+        .map_err(|_| "failed to lock hotkey registry".to_string())?;
+
+    // 冲突检测：同一快捷键不能绑定到两个不同动作。
+    if let Some((existing, _)) = state
+        .iter()
+        .find(|(id, sc)| **id != action && **sc == shortcut)
+    {
+        return Err(format!(
+            "shortcut '{}' conflicts with action '{}'",
+            shortcut_string,
+            existing.as_str()
+        ));
+    }
+
+    if let Some(old) = state.remove(&action) {
         let _ = manager.unregister(old);
     }
 
     manager
-        .register(shortcut)
-        .map_err(|e| format!("failed to register global shortcut: {}", e))?;
-    *state = Some(
-        shortcut_string
-            .parse()
-            .map_err(|e| format!("failed to store registered hotkey: {}", e))?,
-    );
+        .register(shortcut.clone())
+        .map_err(|e| format!("action '{}': failed to register global shortcut: {}", action.as_str(), e))?;
+    state.insert(action, shortcut);
 
     Ok(shortcut_string)
 }
 
+/// 解除某个动作的全局快捷键绑定。
+pub fn unregister_action_hotkey<R: Runtime>(
+    app: &AppHandle<R>,
+    action: ActionId,
+) -> Result<(), String> {
+    let manager = app.global_shortcut();
+    let mut state = registry()
+        .lock()
+        .map_err(|_| "failed to lock hotkey registry".to_string())?;
+    if let Some(old) = state.remove(&action) {
+        let _ = manager.unregister(old);
+    }
+    Ok(())
+}
+
+/// 注册主历史窗口的快捷键。保留旧签名，内部转调动作注册表。
+pub fn register_hotkey<R: Runtime>(app: &AppHandle<R>, value: &str) -> Result<String, String> {
+    register_action_hotkey(app, ActionId::ShowHistoryNearCursor, value)
+}
+
+/// 设置中除主窗口外、其余动作对应热键字符串的可变借用，驱动批量注册。
+fn secondary_bindings(settings: &mut Settings) -> [(ActionId, &mut String); 4] {
+    [
+        (ActionId::PasteLastPlainText, &mut settings.hotkey_paste_plain_text),
+        (ActionId::ClearHistory, &mut settings.hotkey_clear_history),
+        (ActionId::ToggleWindowPin, &mut settings.hotkey_toggle_pin),
+        (ActionId::CaptureSelection, &mut settings.hotkey_capture_selection),
+    ]
+}
+
+/// 注册（或解绑）单个动作：空串表示解绑并回空串，非空则注册并返回标准化串。
+fn register_or_clear<R: Runtime>(
+    app: &AppHandle<R>,
+    action: ActionId,
+    value: &str,
+) -> Result<String, String> {
+    let trimmed = value.trim();
+    if trimmed.is_empty() {
+        unregister_action_hotkey(app, action)?;
+        Ok(String::new())
+    } else {
+        register_action_hotkey(app, action, trimmed)
+    }
+}
+
+/// 依据设置注册全部动作热键，就地把规整后的字符串写回 `settings`。
+///
+/// 主窗口动作失败即返回错误（交由调用方提示用户）；其余动作空串表示解绑、非空则
+/// 注册，任一冲突/非法都会向上报错，便于设置界面精确高亮出错的那条绑定。
+pub fn apply_all_hotkeys<R: Runtime>(
+    app: &AppHandle<R>,
+    settings: &mut Settings,
+) -> Result<(), String> {
+    settings.hotkey = register_hotkey(app, &settings.hotkey)?;
+    for (action, value) in secondary_bindings(settings) {
+        *value = register_or_clear(app, action, value)?;
+    }
+    Ok(())
+}
+
 pub fn register_from_settings_or_default<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
     let settings = crate::database::get_settings().map_err(|e| e.to_string())?;
-    match register_hotkey(app, &settings.hotkey) {
-        Ok(_) => Ok(()),
-        Err(_err) => {
-            register_hotkey(app, DEFAULT_SHORTCUT).map(|_| ())
+    if register_action_hotkey(app, ActionId::ShowHistoryNearCursor, &settings.hotkey).is_err() {
+        register_action_hotkey(app, ActionId::ShowHistoryNearCursor, DEFAULT_SHORTCUT)?;
+    }
+    // 其余动作尽力而为注册：单条非法/冲突不应阻断启动，仅记日志后跳过。
+    for (action, value) in [
+        (ActionId::PasteLastPlainText, settings.hotkey_paste_plain_text.as_str()),
+        (ActionId::ClearHistory, settings.hotkey_clear_history.as_str()),
+        (ActionId::ToggleWindowPin, settings.hotkey_toggle_pin.as_str()),
+        (ActionId::CaptureSelection, settings.hotkey_capture_selection.as_str()),
+    ] {
+        if !value.trim().is_empty() {
+            if let Err(e) = register_action_hotkey(app, action, value) {
+                eprintln!("[WARN] skipping hotkey binding: {}", e);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn action_for_shortcut(shortcut: &Shortcut) -> Option<ActionId> {
+    let state = registry().lock().ok()?;
+    state
+        .iter()
+        .find(|(_, sc)| *sc == shortcut)
+        .map(|(id, _)| *id)
+}
+
+fn dispatch_action<R: Runtime>(app: &AppHandle<R>, action: ActionId) {
+    match action {
+        ActionId::ShowHistoryNearCursor => {
+            if !crate::is_frontend_ready() {
+                crate::queue_show_near_cursor_on_ready();
+                return;
+            }
+            let _ = tray::show_main_window_near_cursor(app);
+        }
+        ActionId::PasteLastPlainText => {
+            if let Ok(records) = crate::database::get_history(1, 0) {
+                if let Some(record) = records.into_iter().find(|r| r.content_type != "image") {
+                    let _ = crate::clipboard::set_clipboard_text(&record.content);
+                    let _ = app.emit("tray-paste-entry", record.id);
+                }
+            }
+        }
+        ActionId::ClearHistory => {
+            let _ = crate::database::clear_non_favorite_history();
+            let _ = app.emit("history-changed", ());
+        }
+        ActionId::ToggleWindowPin => {
+            // 直接在后端翻转置顶状态并作用到窗口，内部会广播 `pinned-changed` 同步前端。
+            tray::toggle_pinned_mode(app);
+        }
+        ActionId::CaptureSelection => {
+            if let Ok(id) = crate::selection::capture_selection_to_history(app) {
+                // 可选再次粘贴：通知粘贴流程把刚捕获的选区送回焦点窗口。
+                let _ = app.emit("tray-paste-entry", id);
+            }
         }
     }
 }
 
-pub fn on_shortcut_triggered<R: Runtime>(app: &AppHandle<R>, _shortcut: &Shortcut) {
-    if !crate::is_frontend_ready() {
-        crate::queue_show_near_cursor_on_ready();
-        return;
+pub fn on_shortcut_triggered<R: Runtime>(app: &AppHandle<R>, shortcut: &Shortcut) {
+    match action_for_shortcut(shortcut) {
+        Some(action) => dispatch_action(app, action),
+        // 未登记的快捷键：沿用历史行为，呼出主窗口。
+        None => dispatch_action(app, ActionId::ShowHistoryNearCursor),
     }
-    let _ = tray::show_main_window_near_cursor(app);
 }
@@ -0,0 +1,346 @@
+//! 单实例守卫与本地 IPC 命令通道。
+//!
+//! 首个实例在启动时原子地占据一个本地端点（Unix 域套接字 / Windows 命名管道）并在
+//! 后台线程上监听；此后再次启动时不再拉起第二个进程，而是把自己的 argv 当作一条
+//! 命令转发给正在运行的实例，由其分发到既有的窗口流程。这样用户就能在外部启动器、
+//! 窗口管理器快捷键或脚本里驱动 clipper，而不必只依赖内置全局快捷键。
+
+use tauri::{AppHandle, Manager};
+
+/// 可通过 IPC 转发的命令。
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum IpcCommand {
+    /// 切换主窗口显隐。
+    Toggle,
+    /// 显示主窗口。
+    Show,
+    /// 在光标附近呼出主窗口。
+    ShowNearCursor,
+    /// 隐藏主窗口。
+    Hide,
+    /// 把最近一条文本记录粘贴到焦点窗口。
+    PasteLatest,
+}
+
+impl IpcCommand {
+    fn from_arg(value: &str) -> Option<Self> {
+        match value.trim() {
+            "toggle" => Some(Self::Toggle),
+            "show" => Some(Self::Show),
+            "show-near-cursor" => Some(Self::ShowNearCursor),
+            "hide" => Some(Self::Hide),
+            "paste-latest" => Some(Self::PasteLatest),
+            _ => None,
+        }
+    }
+
+    fn as_arg(self) -> &'static str {
+        match self {
+            Self::Toggle => "toggle",
+            Self::Show => "show",
+            Self::ShowNearCursor => "show-near-cursor",
+            Self::Hide => "hide",
+            Self::PasteLatest => "paste-latest",
+        }
+    }
+}
+
+/// 从进程 argv 解析要转发的命令；缺省在光标附近呼出主窗口。
+fn command_from_args() -> IpcCommand {
+    std::env::args()
+        .nth(1)
+        .as_deref()
+        .and_then(IpcCommand::from_arg)
+        .unwrap_or(IpcCommand::ShowNearCursor)
+}
+
+/// 把收到的命令分发到既有窗口流程。
+fn dispatch(app: &AppHandle, cmd: IpcCommand) {
+    match cmd {
+        IpcCommand::Toggle => {
+            if let Some(window) = app.get_webview_window("main") {
+                if window.is_visible().unwrap_or(false) {
+                    let _ = window.hide();
+                } else {
+                    show_near_cursor(app);
+                }
+            }
+        }
+        IpcCommand::Show => {
+            let _ = crate::tray::show_main_window(app);
+        }
+        IpcCommand::ShowNearCursor => show_near_cursor(app),
+        IpcCommand::Hide => {
+            if let Some(window) = app.get_webview_window("main") {
+                let _ = window.hide();
+            }
+        }
+        IpcCommand::PasteLatest => paste_latest(app),
+    }
+}
+
+/// 呼出窗口：前端就绪则直接定位到光标附近，否则沿用待命队列等前端加载完成。
+fn show_near_cursor(app: &AppHandle) {
+    if crate::is_frontend_ready() {
+        let _ = crate::tray::show_main_window_near_cursor(app);
+    } else {
+        crate::queue_show_near_cursor_on_ready();
+    }
+}
+
+/// 取最近一条非图片记录，走既有的 `paste_record_content` 流程送回焦点窗口。
+///
+/// 顶部可能是图片记录，因此多取一页再挑出最近的非图片项，而非只看第一条。
+fn paste_latest(app: &AppHandle) {
+    let Ok(records) = crate::database::get_history(16, 0) else {
+        return;
+    };
+    if let Some(record) = records.into_iter().find(|r| r.content_type != "image") {
+        let id = record.id;
+        let handle = app.clone();
+        tauri::async_runtime::spawn(async move {
+            let _ = crate::clipboard::paste_record_content(handle, id, None).await;
+        });
+    }
+}
+
+#[cfg(unix)]
+mod platform {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::os::unix::net::{UnixListener, UnixStream};
+    use std::sync::{Mutex, OnceLock};
+    use std::time::Duration;
+
+    /// 单个客户端连接的读取超时，避免挂死的客户端长期占用处理线程。
+    const READ_TIMEOUT: Duration = Duration::from_secs(2);
+
+    /// 已抢占的监听器，在 [`forward_if_secondary`] 绑定、在 [`start_server`] 取出。
+    static BOUND_LISTENER: OnceLock<Mutex<Option<UnixListener>>> = OnceLock::new();
+
+    fn listener_slot() -> &'static Mutex<Option<UnixListener>> {
+        BOUND_LISTENER.get_or_init(|| Mutex::new(None))
+    }
+
+    /// 套接字路径按用户区隔，避免多用户主机上互相串扰或清理到别人的端点。
+    fn socket_path() -> std::path::PathBuf {
+        let dir = std::env::var_os("XDG_RUNTIME_DIR")
+            .map(std::path::PathBuf::from)
+            .unwrap_or_else(std::env::temp_dir);
+        let user = std::env::var("USER")
+            .or_else(|_| std::env::var("LOGNAME"))
+            .unwrap_or_else(|_| "default".to_string());
+        dir.join(format!("clipper-ipc-{}.sock", user))
+    }
+
+    fn stash(listener: UnixListener) {
+        if let Ok(mut slot) = listener_slot().lock() {
+            *slot = Some(listener);
+        }
+    }
+
+    /// 以「能否绑定端点」作为单实例裁决的唯一依据，使占用过程本身原子化。
+    pub fn forward_if_secondary() -> bool {
+        let path = socket_path();
+        match UnixListener::bind(&path) {
+            Ok(listener) => {
+                stash(listener);
+                false
+            }
+            Err(_) => match UnixStream::connect(&path) {
+                Ok(mut stream) => {
+                    let _ = stream.write_all(command_from_args().as_arg().as_bytes());
+                    true
+                }
+                Err(_) => {
+                    // 端点存在却无人应答：主实例崩溃后留下的残留，清理后重新接管。
+                    let _ = std::fs::remove_file(&path);
+                    match UnixListener::bind(&path) {
+                        Ok(listener) => stash(listener),
+                        Err(e) => eprintln!("[WARN] ipc server bind failed (non-fatal): {}", e),
+                    }
+                    false
+                }
+            },
+        }
+    }
+
+    pub fn start_server(app: &AppHandle) {
+        let listener = match listener_slot().lock() {
+            Ok(mut slot) => match slot.take() {
+                Some(listener) => listener,
+                None => return,
+            },
+            Err(_) => return,
+        };
+
+        let app = app.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(stream) = stream else {
+                    continue;
+                };
+                // 每个连接单独处理，避免挂死的客户端阻塞后续命令。
+                let app = app.clone();
+                std::thread::spawn(move || handle_connection(&app, stream));
+            }
+        });
+    }
+
+    fn handle_connection(app: &AppHandle, mut stream: UnixStream) {
+        let _ = stream.set_read_timeout(Some(READ_TIMEOUT));
+        let mut buf = String::new();
+        if stream.read_to_string(&mut buf).is_err() {
+            return;
+        }
+        if let Some(cmd) = IpcCommand::from_arg(&buf) {
+            dispatch(app, cmd);
+        }
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use super::*;
+    use windows_sys::Win32::Foundation::{
+        CloseHandle, ERROR_ALREADY_EXISTS, ERROR_PIPE_BUSY, GetLastError, INVALID_HANDLE_VALUE,
+    };
+    use windows_sys::Win32::Storage::FileSystem::{
+        CreateFileW, ReadFile, WriteFile, GENERIC_WRITE, OPEN_EXISTING,
+    };
+    use windows_sys::Win32::System::Pipes::{
+        ConnectNamedPipe, CreateNamedPipeW, DisconnectNamedPipe, WaitNamedPipeW, PIPE_ACCESS_INBOUND,
+        PIPE_READMODE_BYTE, PIPE_TYPE_BYTE, PIPE_UNLIMITED_INSTANCES, PIPE_WAIT,
+    };
+    use windows_sys::Win32::System::Threading::CreateMutexW;
+
+    const PIPE_NAME: &str = r"\\.\pipe\clipper-ipc";
+    const MUTEX_NAME: &str = r"Local\clipper-single-instance";
+
+    fn wide(value: &str) -> Vec<u16> {
+        value.encode_utf16().chain(std::iter::once(0)).collect()
+    }
+
+    /// 以命名互斥体作为单实例裁决：首个实例创建它并持有至进程退出，
+    /// 后续实例只会得到 `ERROR_ALREADY_EXISTS`，据此原子地判定身份。
+    pub fn forward_if_secondary() -> bool {
+        let mutex_name = wide(MUTEX_NAME);
+        unsafe {
+            // 句柄刻意不关闭：命名互斥体需在整个进程生命周期内保持打开以守卫端点。
+            let _handle = CreateMutexW(std::ptr::null(), 1, mutex_name.as_ptr());
+            let already_running = GetLastError() == ERROR_ALREADY_EXISTS;
+            if already_running {
+                forward_command();
+                return true;
+            }
+        }
+        false
+    }
+
+    /// 把命令写入主实例的命名管道；遇到管道忙则短暂等待后重试。
+    unsafe fn forward_command() {
+        let name = wide(PIPE_NAME);
+        let payload = command_from_args().as_arg().as_bytes();
+
+        for _ in 0..5 {
+            let handle = CreateFileW(
+                name.as_ptr(),
+                GENERIC_WRITE,
+                0,
+                std::ptr::null(),
+                OPEN_EXISTING,
+                0,
+                0,
+            );
+            if handle != INVALID_HANDLE_VALUE {
+                let mut written: u32 = 0;
+                let _ = WriteFile(
+                    handle,
+                    payload.as_ptr(),
+                    payload.len() as u32,
+                    &mut written,
+                    std::ptr::null_mut(),
+                );
+                CloseHandle(handle);
+                return;
+            }
+            if GetLastError() != ERROR_PIPE_BUSY {
+                return;
+            }
+            // 所有管道实例都在忙，等待一个空闲实例后重试。
+            WaitNamedPipeW(name.as_ptr(), 1000);
+        }
+    }
+
+    pub fn start_server(app: &AppHandle) {
+        let app = app.clone();
+        std::thread::spawn(move || {
+            let name = wide(PIPE_NAME);
+            loop {
+                unsafe {
+                    // 每次循环新建一个管道实例，处理完一个客户端后再接收下一个。
+                    let handle = CreateNamedPipeW(
+                        name.as_ptr(),
+                        PIPE_ACCESS_INBOUND,
+                        PIPE_TYPE_BYTE | PIPE_READMODE_BYTE | PIPE_WAIT,
+                        PIPE_UNLIMITED_INSTANCES,
+                        0,
+                        4096,
+                        0,
+                        std::ptr::null(),
+                    );
+                    if handle == INVALID_HANDLE_VALUE {
+                        break;
+                    }
+
+                    // 阻塞直到有客户端连接。
+                    let _ = ConnectNamedPipe(handle, std::ptr::null_mut());
+
+                    let mut buf = [0u8; 256];
+                    let mut read: u32 = 0;
+                    let ok = ReadFile(
+                        handle,
+                        buf.as_mut_ptr(),
+                        buf.len() as u32,
+                        &mut read,
+                        std::ptr::null_mut(),
+                    );
+                    if ok != 0 && read > 0 {
+                        if let Ok(text) = std::str::from_utf8(&buf[..read as usize]) {
+                            if let Some(cmd) = IpcCommand::from_arg(text) {
+                                dispatch(&app, cmd);
+                            }
+                        }
+                    }
+
+                    DisconnectNamedPipe(handle);
+                    CloseHandle(handle);
+                }
+            }
+        });
+    }
+}
+
+/// 既非 Unix 亦非 Windows 的平台上退化为无操作：永远以主实例身份启动，且不开 IPC。
+#[cfg(not(any(unix, windows)))]
+mod platform {
+    use super::*;
+
+    pub fn forward_if_secondary() -> bool {
+        false
+    }
+
+    pub fn start_server(_app: &AppHandle) {}
+}
+
+/// 若已有主实例在运行，则把本次启动的命令转发过去并返回 `true`（调用方应随即退出）。
+///
+/// 没有可连接的主实例时返回 `false`，表示由本实例原子地接管端点。
+pub fn forward_if_secondary() -> bool {
+    platform::forward_if_secondary()
+}
+
+/// 在后台线程上监听端点，把收到的命令分发到窗口流程。
+pub fn start_server(app: &AppHandle) {
+    platform::start_server(app)
+}
@@ -5,6 +5,13 @@ mod clipboard;
 mod tray;
 mod hotkey;
 mod autostart;
+mod selection;
+mod backup;
+mod ipc;
+mod sync;
+mod config;
+mod open;
+mod atomic;
 
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::{Mutex, OnceLock};
@@ -18,9 +25,11 @@ use commands::{
     add_clipboard_record, add_custom_favorite_record,
     delete_clipboard_record, clear_clipboard_history, clear_history_only, clear_favorite_items,
     set_record_favorite_state, set_record_pinned_state,
+    set_master_passphrase, unlock_database,
     export_favorites_json, import_favorites_json,
     export_favorites_to_path, import_favorites_from_path,
     get_app_settings, save_app_settings, suspend_auto_hide, set_frontend_ready,
+    set_pinned_state,
 };
 
 static LAST_GEOMETRY_EVENT_MS: AtomicU64 = AtomicU64::new(0);
@@ -75,6 +84,34 @@ fn auto_hide_is_suspended() -> bool {
     now_ms() < AUTO_HIDE_SUSPEND_UNTIL_MS.load(Ordering::SeqCst)
 }
 
+/// 当前是否处于常驻置顶模式（失焦不自动隐藏）。
+fn window_is_pinned() -> bool {
+    database::get_settings().map(|s| s.pinned).unwrap_or(false)
+}
+
+/// 置顶模式下记下面板当前的停放位置与大小，供下次以置顶方式打开时还原。
+///
+/// 只在面板收起（CloseRequested）时快照一次，避免拖动/缩放过程中在 UI 线程上
+/// 反复写库。位置用外框左上角、大小用内容尺寸，与 `apply_pinned_mode` 的
+/// `set_position`/`set_size` 还原方式一一对应，防止反复存取造成尺寸漂移。
+fn persist_pinned_geometry(window: &tauri::Window) {
+    if window.label() != "main" || !window_is_pinned() {
+        return;
+    }
+    let Ok(position) = window.outer_position() else {
+        return;
+    };
+    let Ok(size) = window.inner_size() else {
+        return;
+    };
+    let _ = database::save_pinned_geometry(
+        position.x,
+        position.y,
+        size.width as i32,
+        size.height as i32,
+    );
+}
+
 pub fn suspend_main_window_auto_hide(ms: u64) {
     let duration_ms = ms.clamp(200, 15_000);
     AUTO_HIDE_SUSPEND_UNTIL_MS.store(now_ms().saturating_add(duration_ms), Ordering::SeqCst);
@@ -94,10 +131,14 @@ fn cursor_is_near_window(window: &tauri::Window) -> Option<bool> {
         Err(_) => return None,
     };
 
-    let left = position.x as f64 - CURSOR_NEAR_WINDOW_MARGIN_PX;
-    let top = position.y as f64 - CURSOR_NEAR_WINDOW_MARGIN_PX;
-    let right = position.x as f64 + size.width as f64 + CURSOR_NEAR_WINDOW_MARGIN_PX;
-    let bottom = position.y as f64 + size.height as f64 + CURSOR_NEAR_WINDOW_MARGIN_PX;
+    // 命中边距以逻辑像素定义，按窗口当前屏的缩放系数换算到物理像素，
+    // 使高 DPI 屏上的判定区域与低 DPI 屏观感一致。
+    let scale = window.scale_factor().unwrap_or(1.0);
+    let margin = CURSOR_NEAR_WINDOW_MARGIN_PX * scale;
+    let left = position.x as f64 - margin;
+    let top = position.y as f64 - margin;
+    let right = position.x as f64 + size.width as f64 + margin;
+    let bottom = position.y as f64 + size.height as f64 + margin;
 
     Some(cursor.x >= left && cursor.x <= right && cursor.y >= top && cursor.y <= bottom)
 }
@@ -207,6 +248,12 @@ pub fn run() {
     FRONTEND_READY.store(false, Ordering::SeqCst);
     PENDING_SHOW_NEAR_CURSOR.store(false, Ordering::SeqCst);
 
+    // 单实例守卫：已有实例在运行时，把本次 argv 作为命令转发后直接退出，
+    // 避免拉起第二个进程。
+    if ipc::forward_if_secondary() {
+        return;
+    }
+
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
@@ -238,6 +285,8 @@ pub fn run() {
             clear_favorite_items,
             set_record_favorite_state,
             set_record_pinned_state,
+            set_master_passphrase,
+            unlock_database,
             export_favorites_json,
             import_favorites_json,
             export_favorites_to_path,
@@ -246,6 +295,8 @@ pub fn run() {
             save_app_settings,
             suspend_auto_hide,
             set_frontend_ready,
+            set_pinned_state,
+            sync::set_sync_config,
             clipboard::start_monitoring,
             clipboard::stop_monitoring,
             clipboard::check_and_read_clipboard,
@@ -253,11 +304,36 @@ pub fn run() {
             clipboard::paste_to_focus_window,
             clipboard::paste_record_content,
             clipboard::set_clipboard_content,
+            selection::get_selection_text,
+            selection::capture_selection,
+            backup::backup_database,
+            backup::restore_database,
+            backup::export_history,
+            backup::import_history,
+            open::open_url,
+            open::open_with,
+            open::list_openers_for,
         ])
         .setup(|app| {
+            // 在打开数据库前登记环境变量里的主口令，使加密库得以解密。
+            database::unlock_from_env();
+
             // Initialize database on startup
             let _ = database::init_database();
 
+            // 启动时探测一次打包沙箱，供后续“打开方式”派生子进程时清洗环境。
+            open::init();
+
+            // 把 SQLite 变更钩子的事件转发到前端，替代定时轮询 get_history。
+            let change_events = database::subscribe();
+            let change_handle = app.handle().clone();
+            std::thread::spawn(move || {
+                use tauri::Emitter;
+                while let Ok(event) = change_events.recv() {
+                    let _ = change_handle.emit("clipboard-change", event);
+                }
+            });
+
             if let Some(main_window) = app.get_webview_window("main") {
                 let _ = main_window.hide();
                 let _ = main_window.set_skip_taskbar(true);
@@ -271,8 +347,20 @@ pub fn run() {
                 if let Ok(settings) = database::get_settings() {
                     let width = settings.menu_width.max(database::MIN_MENU_WIDTH) as f64;
                     let height = settings.menu_height.max(database::MIN_MENU_HEIGHT) as f64;
-                    let _ = main_window.set_size(tauri::Size::Logical(
-                        tauri::LogicalSize::new(width, height),
+
+                    // 尺寸以逻辑像素持久化，按窗口落点所在显示器的缩放系数换算成
+                    // 物理像素再设置，确保在混合 DPI 屏之间观感尺寸保持不变。
+                    let scale = main_window
+                        .current_monitor()
+                        .ok()
+                        .flatten()
+                        .map(|m| m.scale_factor())
+                        .unwrap_or(1.0);
+                    let _ = main_window.set_size(tauri::Size::Physical(
+                        tauri::PhysicalSize::new(
+                            (width * scale).round() as u32,
+                            (height * scale).round() as u32,
+                        ),
                     ));
                 }
             }
@@ -280,9 +368,16 @@ pub fn run() {
             // Create system tray
             let handle = app.handle();
             tray::create_tray(handle)?;
+
+            // 启动本地 IPC 服务，接收后续实例转发来的命令。
+            ipc::start_server(handle);
+
             hotkey::register_from_settings_or_default(handle)?;
             let _ = autostart::sync_from_settings(handle);
 
+            // 监听磁盘上的配置文件，外部编辑即时热重载。
+            config::start_watcher(handle.clone());
+
             // Start clipboard monitoring automatically
             let monitor_handle = handle.clone();
             tauri::async_runtime::spawn(async move {
@@ -295,6 +390,8 @@ pub fn run() {
             tauri::WindowEvent::CloseRequested { api, .. } => {
                 // Prevent close, hide to tray instead
                 api.prevent_close();
+                // 置顶面板收起前记下停放位置，便于下次在原处打开。
+                persist_pinned_geometry(window);
                 let _ = window.hide();
             }
             tauri::WindowEvent::Moved(_) => {
@@ -308,7 +405,20 @@ pub fn run() {
                     LAST_GEOMETRY_EVENT_MS.store(now_ms(), Ordering::SeqCst);
                 }
                 if window.label() == "main" {
-                    schedule_window_size_persist(size.width as i32, size.height as i32);
+                    // 置顶面板的尺寸单独持久化，不能覆盖普通弹窗记忆的大小。
+                    if !window_is_pinned() {
+                        // 以逻辑像素持久化，跨显示器移动时保持观感尺寸不变。
+                        let scale = window.scale_factor().unwrap_or(1.0);
+                        let logical_w = (size.width as f64 / scale).round() as i32;
+                        let logical_h = (size.height as f64 / scale).round() as i32;
+                        schedule_window_size_persist(logical_w, logical_h);
+                    }
+                    clamp_main_window_to_work_area(window);
+                }
+            }
+            tauri::WindowEvent::ScaleFactorChanged { .. } => {
+                // 窗口跨越不同 DPI 的显示器后重新夹取，避免越界或错位。
+                if window.label() == "main" {
                     clamp_main_window_to_work_area(window);
                 }
             }
@@ -316,6 +426,10 @@ pub fn run() {
                 if window.label() != "main" {
                     return;
                 }
+                // 置顶模式下窗口像常驻浮动面板一样停留，失焦不再自动隐藏。
+                if window_is_pinned() {
+                    return;
+                }
                 if auto_hide_is_suspended() {
                     return;
                 }
@@ -1,10 +1,29 @@
 use serde::{Deserialize, Serialize};
 
+/// 一条剪贴板记录附带的单个原始格式快照。
+///
+/// Excel、矢量绘图等应用会同时往剪贴板放多种私有格式；把它们逐一快照保存，粘贴
+/// 时原样回填即可无损还原，而不是退化成纯文本或 PNG。
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ClipboardFormatBlob {
+    /// Windows 剪贴板格式 id（标准格式为固定值，注册格式 >= 0xC000）。
+    pub format_id: u32,
+    /// 注册格式的名称，用于粘贴时按名重新注册以取得当前会话的 id；标准格式为 `None`。
+    pub name: Option<String>,
+    /// 该格式的原始字节。
+    pub bytes: Vec<u8>,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ClipboardRecord {
     pub id: i64,
     pub content_type: String, // "text" | "image" | "html" | "link"
     pub content: String,
+    /// `content_type` 为 `"html"` 时保存富文本 HTML 正文；`content` 则是其纯文本影子。
+    /// 其它类型恒为 `None`。
+    pub html: Option<String>,
+    /// 多格式捕获模式下保存的全部原始剪贴板格式；仅当源应用附带私有格式时非空。
+    pub bundle: Option<Vec<ClipboardFormatBlob>>,
     pub image_data: Option<Vec<u8>>,
     pub is_favorite: bool,
     pub is_pinned: bool,
@@ -12,15 +31,86 @@ pub struct ClipboardRecord {
     pub created_at: String,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Settings {
     pub hotkey_modifiers: u32,
     pub hotkey_key: u32,
     pub hotkey: String, // global shortcut like "Ctrl+Shift+V"
+    /// 以纯文本粘贴最近一条记录的全局快捷键；留空表示未绑定。
+    pub hotkey_paste_plain_text: String,
+    /// 清空非收藏历史的全局快捷键；留空表示未绑定。
+    pub hotkey_clear_history: String,
+    /// 切换窗口置顶的全局快捷键；留空表示未绑定。
+    pub hotkey_toggle_pin: String,
+    /// 抓取焦点应用选区的全局快捷键；留空表示未绑定。
+    pub hotkey_capture_selection: String,
     pub theme: String,  // "light" | "dark" | "system"
     pub keep_days: i32,
     pub max_records: i32,
     pub auto_start: bool,
+    /// 弹窗以「悬浮面板」形式呈现：置顶、不进任务栏、失焦自动隐藏。
+    pub overlay_mode: bool,
+    /// 常驻置顶模式：窗口恒置顶、回到任务栏，并关闭失焦自动隐藏，像固定浮窗一样停留。
+    pub pinned: bool,
+    /// 是否记录图片剪贴板（用感知哈希去重近乎相同的重复复制）。
+    pub enable_image_recording: bool,
+    /// 连接池各连接的 `PRAGMA busy_timeout`（毫秒），遇锁自动重试而非立即报错。
+    pub busy_timeout_ms: u64,
+}
+
+/// 历史搜索的匹配模式。
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchMode {
+    /// 转义后的 `LIKE` 子串匹配（保持原有行为）。
+    Exact,
+    /// 每个词后追加 `*` 做前缀匹配。
+    Prefix,
+    /// 把关键字拆成单字并以通配符连接的模糊匹配。
+    Fuzzy,
+    /// FTS5 `MATCH` 全文检索。
+    FullText,
+}
+
+impl Default for SearchMode {
+    fn default() -> Self {
+        SearchMode::Exact
+    }
+}
+
+/// `clipboard_history` 行级变更的类型。
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeAction {
+    Insert,
+    Update,
+    Delete,
+}
+
+/// 一条由 SQLite update_hook 捕获、在事务提交后广播的变更事件。
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct ChangeEvent {
+    pub action: ChangeAction,
+    pub rowid: i64,
+}
+
+/// 历史记录的结构化查询条件。
+///
+/// 所有字段均为可选过滤项，留空表示不限制；`keyword` 配合 [`SearchMode`] 既可做
+/// 转义 `LIKE` 子串匹配，也可走 FTS5 全文检索。时间边界 `before` / `after` 与
+/// `created_at` 采用同样的字符串格式比较。
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct HistoryFilter {
+    pub content_type: Option<String>,
+    pub source_app: Option<String>,
+    /// 仅保留 `created_at >= after` 的记录。
+    pub after: Option<String>,
+    /// 仅保留 `created_at <= before` 的记录。
+    pub before: Option<String>,
+    pub favorites_only: bool,
+    pub pinned_only: bool,
+    pub keyword: Option<String>,
+    pub mode: SearchMode,
 }
 
 impl Default for Settings {
@@ -29,10 +119,18 @@ impl Default for Settings {
             hotkey_modifiers: 0,
             hotkey_key: 0,
             hotkey: "Ctrl+Shift+V".to_string(),
+            hotkey_paste_plain_text: String::new(),
+            hotkey_clear_history: String::new(),
+            hotkey_toggle_pin: String::new(),
+            hotkey_capture_selection: String::new(),
             theme: "system".to_string(),
             keep_days: 1,
             max_records: 500,
             auto_start: false,
+            overlay_mode: true,
+            pinned: false,
+            enable_image_recording: false,
+            busy_timeout_ms: 5000,
         }
     }
 }
@@ -0,0 +1,457 @@
+//! “打开方式”子系统。
+//!
+//! 取代过去 `open_url` 里对 `opener::open` 的裸调用，解决两个问题：
+//!
+//! 1. **沙箱环境泄漏**：AppImage/Snap/Flatpak 打包运行时，`PATH`、`LD_LIBRARY_PATH`、
+//!    `XDG_*` 等变量会指回 bundle 内部，若原样继承给外部程序，后者会加载到 bundle 里
+//!    不兼容的库而崩溃或找不到自身依赖。启动时探测沙箱，派生子进程前把这些 PATH 类
+//!    变量中落在 bundle 前缀下的条目剔除、去重，整条为空则直接 unset。
+//! 2. **打开方式选择**：`list_openers` 枚举某个链接/文件可用的处理程序，`open_with`
+//!    用指定程序（或系统默认）打开，让用户右键精确选择。
+
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+use serde::Serialize;
+
+/// 一个可用于打开目标的处理程序。
+#[derive(Clone, Debug, Serialize)]
+pub struct OpenerApp {
+    /// 平台内稳定标识：Linux 为 `.desktop` 文件名，Windows 为可执行文件路径，
+    /// macOS 为应用 bundle 路径。
+    pub id: String,
+    /// 展示名称。
+    pub name: String,
+}
+
+/// 运行时沙箱信息：是否处于打包沙箱，以及其 bundle 前缀（用于清洗 PATH 类变量）。
+#[derive(Clone, Debug)]
+pub struct Sandbox {
+    pub active: bool,
+    pub prefix: Option<PathBuf>,
+}
+
+static SANDBOX: OnceLock<Sandbox> = OnceLock::new();
+
+/// 启动时探测一次沙箱环境并缓存，后续派生子进程时复用。
+pub fn init() {
+    let _ = sandbox();
+}
+
+fn sandbox() -> &'static Sandbox {
+    SANDBOX.get_or_init(detect_sandbox)
+}
+
+/// 通过 `APPIMAGE` / `SNAP` / `FLATPAK_ID` 环境标记判断打包形态并取其 bundle 前缀。
+fn detect_sandbox() -> Sandbox {
+    // AppImage：优先用运行器导出的 APPDIR，其次退回镜像文件所在目录。
+    if std::env::var_os("APPIMAGE").is_some() {
+        let prefix = std::env::var_os("APPDIR")
+            .map(PathBuf::from)
+            .or_else(|| {
+                std::env::var_os("APPIMAGE")
+                    .map(PathBuf::from)
+                    .and_then(|p| p.parent().map(PathBuf::from))
+            });
+        return Sandbox { active: true, prefix };
+    }
+    if let Some(snap) = std::env::var_os("SNAP") {
+        return Sandbox { active: true, prefix: Some(PathBuf::from(snap)) };
+    }
+    if std::env::var_os("FLATPAK_ID").is_some() {
+        return Sandbox { active: true, prefix: Some(PathBuf::from("/app")) };
+    }
+    Sandbox { active: false, prefix: None }
+}
+
+/// 值为 `:` 分隔路径列表、需要在沙箱里剔除 bundle 条目的环境变量。
+#[cfg(target_os = "linux")]
+const PATH_LIKE_VARS: &[&str] = &[
+    "PATH",
+    "LD_LIBRARY_PATH",
+    "XDG_DATA_DIRS",
+    "XDG_CONFIG_DIRS",
+    "GST_PLUGIN_SYSTEM_PATH",
+    "GIO_EXTRA_MODULES",
+    "GTK_PATH",
+];
+
+/// 在沙箱里规整子进程的 PATH 类环境：剔除指回 bundle 的条目、按首次出现去重，
+/// 整条清空后 unset，使外部程序用系统自身的库与数据目录。
+#[cfg(target_os = "linux")]
+fn sanitize_child_env(cmd: &mut std::process::Command) {
+    use std::collections::HashSet;
+    use std::path::Path;
+
+    let sandbox = sandbox();
+    let Some(prefix) = sandbox.prefix.as_deref() else {
+        return;
+    };
+
+    for var in PATH_LIKE_VARS {
+        let Ok(value) = std::env::var(var) else {
+            continue;
+        };
+        let mut seen = HashSet::new();
+        let cleaned: Vec<&str> = value
+            .split(':')
+            .filter(|entry| !entry.is_empty())
+            .filter(|entry| !Path::new(entry).starts_with(prefix))
+            .filter(|entry| seen.insert(entry.to_string()))
+            .collect();
+
+        if cleaned.is_empty() {
+            cmd.env_remove(var);
+        } else {
+            cmd.env(var, cleaned.join(":"));
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn sanitize_child_env(_cmd: &mut std::process::Command) {}
+
+/// 用指定程序（`app_id` 为 `None` 时用系统默认）打开目标，派生前清洗沙箱环境。
+pub fn open_target(target: &str, app_id: Option<&str>) -> Result<(), String> {
+    platform::open_target(target, app_id)
+}
+
+/// 枚举可打开 `target` 的处理程序。
+pub fn list_openers(target: &str) -> Result<Vec<OpenerApp>, String> {
+    platform::list_openers(target)
+}
+
+/// 用系统默认程序打开链接/文件。保留旧命令名，内部转调规整后的打开路径。
+#[tauri::command]
+pub fn open_url(url: String) -> Result<(), String> {
+    open_target(&url, None)
+}
+
+/// 用指定程序（或系统默认）打开目标。
+#[tauri::command]
+pub fn open_with(target: String, app_id: Option<String>) -> Result<(), String> {
+    open_target(&target, app_id.as_deref())
+}
+
+/// 列出可打开目标的处理程序，供前端展示“打开方式”选择。
+#[tauri::command]
+pub fn list_openers_for(target: String) -> Result<Vec<OpenerApp>, String> {
+    list_openers(&target)
+}
+
+/// 从 `scheme://...` 形式的目标里取协议名（全小写），文件路径返回 `None`。
+fn url_scheme(target: &str) -> Option<String> {
+    let (scheme, rest) = target.split_once(':')?;
+    if rest.starts_with("//")
+        && !scheme.is_empty()
+        && scheme.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+    {
+        Some(scheme.to_ascii_lowercase())
+    } else {
+        None
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::{sanitize_child_env, url_scheme, OpenerApp};
+    use std::io::{BufRead, BufReader};
+    use std::path::PathBuf;
+    use std::process::Command;
+
+    pub fn open_target(target: &str, app_id: Option<&str>) -> Result<(), String> {
+        let mut cmd = match app_id {
+            // gtk-launch 按 .desktop id 启动应用，并把目标作为待打开的 URI/文件传入。
+            Some(id) => {
+                let mut c = Command::new("gtk-launch");
+                c.arg(strip_desktop_suffix(id)).arg(target);
+                c
+            }
+            None => {
+                let mut c = Command::new("xdg-open");
+                c.arg(target);
+                c
+            }
+        };
+        sanitize_child_env(&mut cmd);
+        cmd.spawn()
+            .map(|_| ())
+            .map_err(|e| format!("failed to launch opener: {}", e))
+    }
+
+    pub fn list_openers(target: &str) -> Result<Vec<OpenerApp>, String> {
+        let mime = target_mime(target)?;
+        let mut apps = Vec::new();
+        let mut seen = std::collections::HashSet::new();
+
+        for dir in application_dirs() {
+            let Ok(entries) = std::fs::read_dir(&dir) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if path.extension().and_then(|e| e.to_str()) != Some("desktop") {
+                    continue;
+                }
+                let Some(file_name) = path.file_name().and_then(|n| n.to_str()) else {
+                    continue;
+                };
+                if !seen.insert(file_name.to_string()) {
+                    continue; // 高优先目录里的同名条目优先，后续覆盖忽略。
+                }
+                if let Some(app) = parse_desktop_entry(&path, &mime, file_name) {
+                    apps.push(app);
+                }
+            }
+        }
+
+        Ok(apps)
+    }
+
+    /// 目标对应的 MIME：URL 取 `x-scheme-handler/<scheme>`，文件交给 `xdg-mime` 判别。
+    fn target_mime(target: &str) -> Result<String, String> {
+        if let Some(scheme) = url_scheme(target) {
+            return Ok(format!("x-scheme-handler/{}", scheme));
+        }
+        let output = Command::new("xdg-mime")
+            .args(["query", "filetype", target])
+            .output()
+            .map_err(|e| format!("failed to query mime type: {}", e))?;
+        let mime = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        if mime.is_empty() {
+            Err(format!("could not determine mime type for '{}'", target))
+        } else {
+            Ok(mime)
+        }
+    }
+
+    fn application_dirs() -> Vec<PathBuf> {
+        let mut dirs = Vec::new();
+        if let Some(home) = std::env::var_os("XDG_DATA_HOME") {
+            dirs.push(PathBuf::from(home));
+        } else if let Some(home) = std::env::var_os("HOME") {
+            dirs.push(PathBuf::from(home).join(".local/share"));
+        }
+        let system = std::env::var("XDG_DATA_DIRS")
+            .unwrap_or_else(|_| "/usr/local/share:/usr/share".to_string());
+        for dir in system.split(':').filter(|d| !d.is_empty()) {
+            dirs.push(PathBuf::from(dir));
+        }
+        dirs.into_iter().map(|d| d.join("applications")).collect()
+    }
+
+    /// 读取 `.desktop` 的 `[Desktop Entry]` 段，命中目标 MIME 时返回名称与 `.desktop` 文件名。
+    fn parse_desktop_entry(path: &std::path::Path, mime: &str, id: &str) -> Option<OpenerApp> {
+        let file = std::fs::File::open(path).ok()?;
+        let mut in_entry = false;
+        let mut name: Option<String> = None;
+        let mut handles = false;
+        let mut hidden = false;
+
+        for line in BufReader::new(file).lines().map_while(Result::ok) {
+            let line = line.trim();
+            if line.starts_with('[') {
+                in_entry = line == "[Desktop Entry]";
+                continue;
+            }
+            if !in_entry {
+                continue;
+            }
+            if let Some(value) = line.strip_prefix("Name=") {
+                name.get_or_insert_with(|| value.to_string());
+            } else if let Some(value) = line.strip_prefix("MimeType=") {
+                handles = value.split(';').any(|m| m == mime);
+            } else if let Some(value) = line.strip_prefix("NoDisplay=") {
+                hidden |= value.eq_ignore_ascii_case("true");
+            } else if let Some(value) = line.strip_prefix("Hidden=") {
+                hidden |= value.eq_ignore_ascii_case("true");
+            }
+        }
+
+        if handles && !hidden {
+            Some(OpenerApp {
+                id: id.to_string(),
+                name: name.unwrap_or_else(|| id.to_string()),
+            })
+        } else {
+            None
+        }
+    }
+
+    fn strip_desktop_suffix(id: &str) -> &str {
+        id.strip_suffix(".desktop").unwrap_or(id)
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::OpenerApp;
+    use std::process::Command;
+
+    pub fn open_target(target: &str, app_id: Option<&str>) -> Result<(), String> {
+        // macOS 不存在 Linux 那样的 bundle 库泄漏问题，直接交给 `open`；指定应用时用
+        // 其 bundle 路径（`-a`）。
+        let mut cmd = Command::new("/usr/bin/open");
+        if let Some(app) = app_id {
+            cmd.arg("-a").arg(app);
+        }
+        cmd.arg(target);
+        cmd.spawn()
+            .map(|_| ())
+            .map_err(|e| format!("failed to launch opener: {}", e))
+    }
+
+    pub fn list_openers(target: &str) -> Result<Vec<OpenerApp>, String> {
+        ls_copy_application_urls(target)
+    }
+
+    /// 经 LaunchServices 的 `LSCopyApplicationURLsForURL` 取能打开该 URL 的全部应用。
+    fn ls_copy_application_urls(target: &str) -> Result<Vec<OpenerApp>, String> {
+        use core_foundation::array::CFArray;
+        use core_foundation::base::TCFType;
+        use core_foundation::string::CFString;
+        use core_foundation::url::CFURL;
+
+        // 文件路径与 URL 分别构造 CFURL。
+        let cf_url = if target.contains("://") {
+            let s = CFString::new(target);
+            unsafe {
+                let raw = CFURLCreateWithString(std::ptr::null(), s.as_concrete_TypeRef(), std::ptr::null());
+                if raw.is_null() {
+                    return Err("invalid url".to_string());
+                }
+                CFURL::wrap_under_create_rule(raw)
+            }
+        } else {
+            CFURL::from_path(target, false).ok_or_else(|| "invalid path".to_string())?
+        };
+
+        let mut apps = Vec::new();
+        unsafe {
+            let raw = LSCopyApplicationURLsForURL(cf_url.as_concrete_TypeRef(), LS_ROLES_ALL);
+            if raw.is_null() {
+                return Ok(apps);
+            }
+            let array: CFArray<CFURL> = CFArray::wrap_under_create_rule(raw as _);
+            for url in array.iter() {
+                if let Some(path) = url.to_path() {
+                    let id = path.to_string_lossy().to_string();
+                    let name = path
+                        .file_stem()
+                        .map(|s| s.to_string_lossy().to_string())
+                        .unwrap_or_else(|| id.clone());
+                    apps.push(OpenerApp { id, name });
+                }
+            }
+        }
+        Ok(apps)
+    }
+
+    const LS_ROLES_ALL: u32 = 0xFFFF_FFFF;
+
+    #[link(name = "CoreServices", kind = "framework")]
+    extern "C" {
+        fn LSCopyApplicationURLsForURL(
+            url: core_foundation::url::CFURLRef,
+            roles: u32,
+        ) -> core_foundation::array::CFArrayRef;
+    }
+
+    #[link(name = "CoreFoundation", kind = "framework")]
+    extern "C" {
+        fn CFURLCreateWithString(
+            allocator: core_foundation::base::CFAllocatorRef,
+            url_string: core_foundation::string::CFStringRef,
+            base_url: core_foundation::url::CFURLRef,
+        ) -> core_foundation::url::CFURLRef;
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::{sanitize_child_env, url_scheme, OpenerApp};
+    use std::os::windows::ffi::{OsStrExt, OsStringExt};
+    use std::process::Command;
+
+    use windows_sys::Win32::UI::Shell::{AssocQueryStringW, ASSOCF_NONE, ASSOCSTR_EXECUTABLE};
+
+    pub fn open_target(target: &str, app_id: Option<&str>) -> Result<(), String> {
+        let mut cmd = match app_id {
+            // app_id 为具体可执行文件路径，直接用它打开目标。
+            Some(exe) => {
+                let mut c = Command::new(exe);
+                c.arg(target);
+                c
+            }
+            // 无指定程序时走 shell 关联，等价于资源管理器的默认“打开”。
+            None => {
+                let mut c = Command::new("cmd");
+                c.args(["/C", "start", "", target]);
+                c
+            }
+        };
+        sanitize_child_env(&mut cmd);
+        cmd.spawn()
+            .map(|_| ())
+            .map_err(|e| format!("failed to launch opener: {}", e))
+    }
+
+    pub fn list_openers(target: &str) -> Result<Vec<OpenerApp>, String> {
+        // 注册表关联：URL 按协议、文件按扩展名查出默认处理程序的可执行文件。
+        let assoc = match url_scheme(target) {
+            Some(scheme) => scheme,
+            None => std::path::Path::new(target)
+                .extension()
+                .and_then(|e| e.to_str())
+                .map(|e| format!(".{}", e))
+                .ok_or_else(|| format!("could not determine association for '{}'", target))?,
+        };
+
+        match query_executable(&assoc) {
+            Some(exe) => {
+                let name = std::path::Path::new(&exe)
+                    .file_stem()
+                    .map(|s| s.to_string_lossy().to_string())
+                    .unwrap_or_else(|| exe.clone());
+                Ok(vec![OpenerApp { id: exe, name }])
+            }
+            None => Ok(Vec::new()),
+        }
+    }
+
+    fn query_executable(assoc: &str) -> Option<String> {
+        let wide: Vec<u16> = std::ffi::OsStr::new(assoc)
+            .encode_wide()
+            .chain(std::iter::once(0))
+            .collect();
+        unsafe {
+            let mut len: u32 = 0;
+            // 先取所需长度。
+            AssocQueryStringW(
+                ASSOCF_NONE,
+                ASSOCSTR_EXECUTABLE,
+                wide.as_ptr(),
+                std::ptr::null(),
+                std::ptr::null_mut(),
+                &mut len,
+            );
+            if len == 0 {
+                return None;
+            }
+            let mut buffer = vec![0u16; len as usize];
+            let hr = AssocQueryStringW(
+                ASSOCF_NONE,
+                ASSOCSTR_EXECUTABLE,
+                wide.as_ptr(),
+                std::ptr::null(),
+                buffer.as_mut_ptr(),
+                &mut len,
+            );
+            if hr != 0 {
+                return None;
+            }
+            let end = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
+            Some(std::ffi::OsString::from_wide(&buffer[..end]).to_string_lossy().to_string())
+        }
+    }
+}
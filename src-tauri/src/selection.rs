@@ -0,0 +1,138 @@
+use arboard::Clipboard;
+use enigo::{Enigo, Key, KeyboardControllable};
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Runtime};
+
+use crate::models::ClipboardRecord;
+
+/// 合成复制按键后等待系统把选区写入剪贴板的时间。
+const SELECTION_SETTLE_MS: u64 = 100;
+
+fn read_clipboard_text() -> Option<String> {
+    Clipboard::new().ok().and_then(|mut c| c.get_text().ok())
+}
+
+/// 向当前焦点窗口合成一次复制快捷键（Windows/Linux: Ctrl+C，macOS: Cmd+C）。
+fn synthesize_copy() {
+    let mut enigo = Enigo::new();
+
+    #[cfg(target_os = "macos")]
+    let modifier = Key::Meta;
+    #[cfg(not(target_os = "macos"))]
+    let modifier = Key::Control;
+
+    enigo.key_down(modifier);
+    enigo.key_click(Key::Layout('c'));
+    enigo.key_up(modifier);
+}
+
+/// 抓取当前焦点应用中选中的文本（而不是剪贴板里已有的内容）。
+///
+/// 实现上先保存当前剪贴板，合成一次复制按键，等待系统写入后读取结果，最后把原
+/// 剪贴板内容恢复回去，避免污染用户自己的复制缓冲区。未能捕获到选区时返回错误
+/// （读到的内容为空或与复制前的剪贴板一致，都视为未捕获到选区）。
+#[tauri::command]
+pub fn get_selection_text() -> Result<String, String> {
+    let previous = read_clipboard_text();
+
+    synthesize_copy();
+    thread::sleep(Duration::from_millis(SELECTION_SETTLE_MS));
+    let captured = read_clipboard_text();
+
+    // 恢复用户原本的剪贴板内容。
+    if let Some(prev) = &previous {
+        let _ = crate::clipboard::set_clipboard_text(prev);
+    }
+
+    match captured {
+        Some(text)
+            if !text.trim().is_empty() && previous.as_deref() != Some(text.as_str()) =>
+        {
+            Ok(text)
+        }
+        _ => Err("no selection captured".to_string()),
+    }
+}
+
+/// 抓取焦点应用的选区并直接压入历史，全程不打开窗口。
+///
+/// 先快照当前剪贴板，合成一次复制按键并等待系统写入，再与快照比对以判断是否真的
+/// 发生了复制——内容为空或与复制前完全一致都视为「无选区」。确有新内容时作为新
+/// 记录插入，随后把原剪贴板恢复回去，使用户原本的复制缓冲区不受影响。两次对剪贴
+/// 板的写入都先经 [`crate::clipboard::ignore_next_change`] 打标，避免监听器把它们
+/// 当作用户复制而重复记录。返回新记录 id；未捕获到选区时返回 `None`。
+fn capture_selection_impl<R: Runtime>(app: &AppHandle<R>) -> Result<Option<i64>, String> {
+    let previous = read_clipboard_text();
+
+    // 合成的复制会改写剪贴板并触发监听器，这一次变化交由本流程自行记录。
+    crate::clipboard::ignore_next_change();
+    synthesize_copy();
+    thread::sleep(Duration::from_millis(SELECTION_SETTLE_MS));
+    let captured = read_clipboard_text();
+
+    // 比对快照：内容为空、全是空白或与复制前一致，说明焦点窗口没有可复制的选区。
+    let new_text = match captured {
+        Some(text)
+            if !text.trim().is_empty() && previous.as_deref() != Some(text.as_str()) =>
+        {
+            text
+        }
+        _ => {
+            // 合成的复制没有改动剪贴板，预先打的忽略标记不会被任何变化消费，
+            // 这里主动撤销，以免残留到用户下一次真实复制。
+            crate::clipboard::clear_ignore_next_change();
+            return Ok(None);
+        }
+    };
+
+    let content_type = if crate::clipboard::is_url(&new_text) {
+        "link"
+    } else {
+        "text"
+    };
+
+    let record = ClipboardRecord {
+        id: 0,
+        content_type: content_type.to_string(),
+        content: new_text,
+        html: None,
+        bundle: None,
+        image_data: None,
+        is_favorite: false,
+        is_pinned: false,
+        source_app: crate::clipboard::get_source_app(),
+        created_at: chrono::Local::now().to_rfc3339(),
+    };
+
+    let id = crate::database::add_record(record).map_err(|e| e.to_string())?;
+
+    restore_clipboard(previous.as_deref());
+    let _ = app.emit("history-changed", ());
+    Ok(Some(id))
+}
+
+/// 把先前快照的剪贴板内容写回，同样打标避免被重复记录。
+fn restore_clipboard(previous: Option<&str>) {
+    if let Some(prev) = previous {
+        crate::clipboard::ignore_next_change();
+        let _ = crate::clipboard::set_clipboard_text(prev);
+    }
+}
+
+/// `capture_selection` 命令：抓取焦点选区并存入历史，返回新记录 id（无选区时为
+/// `None`），供前端或外部触发直接调用。
+#[tauri::command]
+pub fn capture_selection(app: AppHandle) -> Result<Option<i64>, String> {
+    capture_selection_impl(&app)
+}
+
+/// 捕获当前选区并写入历史记录，供全局快捷键动作调用。
+///
+/// 返回新记录的 id；当没有可捕获的选区时返回错误。
+pub fn capture_selection_to_history<R: Runtime>(app: &AppHandle<R>) -> Result<i64, String> {
+    match capture_selection_impl(app)? {
+        Some(id) => Ok(id),
+        None => Err("no selection captured".to_string()),
+    }
+}
@@ -0,0 +1,278 @@
+//! 端到端加密的跨设备剪贴板同步。
+//!
+//! 每台设备用同一个口令派生出 AES-256 密钥（`SHA-256(password)`），把本地新捕获的
+//! 文本加密后 POST 到一个共享 HTTP 端点，并轮询该端点取回其它设备推送的内容。明文
+//! 只在各设备本地出现，服务端看到的始终是 `base64(IV ‖ 密文)`。
+//!
+//! 为避免「取回自己刚推送的内容 → 再次写剪贴板 → 再次推送」的回环，每条负载都带一个
+//! 由设备标识与时间戳拼成的 magic；推送过的 magic 记在最近集合里，轮询时遇到自己的
+//! 或已处理过的 magic 直接跳过。
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use aes::Aes256;
+use base64::Engine;
+use cbc::cipher::{block_padding::Pkcs7, BlockDecryptMut, BlockEncryptMut, KeyIvInit};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Emitter};
+
+use crate::models::ClipboardRecord;
+
+type Aes256CbcEnc = cbc::Encryptor<Aes256>;
+type Aes256CbcDec = cbc::Decryptor<Aes256>;
+
+/// 轮询远端的时间间隔。
+const POLL_INTERVAL: Duration = Duration::from_secs(3);
+/// 最近处理过的 magic 上限，超过后丢弃最旧的。
+const SEEN_CAPACITY: usize = 64;
+
+/// 同步配置：目标端点与由口令派生出的对称密钥。
+struct SyncConfig {
+    url: String,
+    key: [u8; 32],
+}
+
+fn config() -> &'static Mutex<Option<SyncConfig>> {
+    static CONFIG: OnceLock<Mutex<Option<SyncConfig>>> = OnceLock::new();
+    CONFIG.get_or_init(|| Mutex::new(None))
+}
+
+fn seen() -> &'static Mutex<VecDeque<String>> {
+    static SEEN: OnceLock<Mutex<VecDeque<String>>> = OnceLock::new();
+    SEEN.get_or_init(|| Mutex::new(VecDeque::new()))
+}
+
+/// 轮询线程是否已在运行，避免重复启动。
+static POLLING: AtomicBool = AtomicBool::new(false);
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// 本设备的稳定标识，用于区分自己推送的负载。
+///
+/// 持久化到一个小文件，跨重启保持不变——否则重启后拿到新标识，会把自己之前推送、
+/// 仍留在端点上的内容当成远端内容重新写回并回推，造成旧内容复活与广播。
+fn device_id() -> &'static str {
+    static DEVICE_ID: OnceLock<String> = OnceLock::new();
+    DEVICE_ID.get_or_init(load_or_create_device_id)
+}
+
+fn load_or_create_device_id() -> String {
+    let path = std::env::temp_dir().join("clipper-device-id");
+    if let Ok(existing) = std::fs::read_to_string(&path) {
+        let trimmed = existing.trim();
+        if !trimmed.is_empty() {
+            return trimmed.to_string();
+        }
+    }
+
+    let mut bytes = [0u8; 16];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    let id: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    let _ = std::fs::write(&path, &id);
+    id
+}
+
+/// 进程内复用的 HTTP 客户端，避免每次推送都重建连接池。
+fn http_client() -> &'static reqwest::blocking::Client {
+    static CLIENT: OnceLock<reqwest::blocking::Client> = OnceLock::new();
+    CLIENT.get_or_init(reqwest::blocking::Client::new)
+}
+
+fn base64_engine() -> base64::engine::GeneralPurpose {
+    base64::engine::general_purpose::STANDARD
+}
+
+/// 由口令派生 32 字节 AES-256 密钥。
+fn derive_key(password: &str) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(password.as_bytes());
+    hasher.finalize().into()
+}
+
+/// 加密为 `base64(IV ‖ 密文)`，IV 为每次随机生成的 16 字节。
+fn encrypt(plaintext: &[u8], key: &[u8; 32]) -> String {
+    let mut iv = [0u8; 16];
+    rand::thread_rng().fill_bytes(&mut iv);
+
+    let ciphertext = Aes256CbcEnc::new(key.into(), &iv.into())
+        .encrypt_padded_vec_mut::<Pkcs7>(plaintext);
+
+    let mut framed = iv.to_vec();
+    framed.extend_from_slice(&ciphertext);
+    base64_engine().encode(framed)
+}
+
+/// 还原 [`encrypt`] 产出的负载；格式不符或口令不匹配时返回 `None`。
+fn decrypt(payload: &str, key: &[u8; 32]) -> Option<Vec<u8>> {
+    let framed = base64_engine().decode(payload.trim()).ok()?;
+    if framed.len() <= 16 {
+        return None;
+    }
+    let (iv, ciphertext) = framed.split_at(16);
+    Aes256CbcDec::new(key.into(), iv.into())
+        .decrypt_padded_vec_mut::<Pkcs7>(ciphertext)
+        .ok()
+}
+
+/// 在端点间传输的负载（加密前的明文结构）。
+#[derive(Serialize, Deserialize)]
+struct SyncPayload {
+    magic: String,
+    content: String,
+}
+
+/// 记录一个已处理的 magic，维持最近集合的容量上限。
+fn remember(magic: String) {
+    if let Ok(mut seen) = seen().lock() {
+        if seen.iter().any(|m| m == &magic) {
+            return;
+        }
+        seen.push_back(magic);
+        while seen.len() > SEEN_CAPACITY {
+            seen.pop_front();
+        }
+    }
+}
+
+fn already_seen(magic: &str) -> bool {
+    seen()
+        .lock()
+        .map(|seen| seen.iter().any(|m| m == magic))
+        .unwrap_or(false)
+}
+
+/// 设置同步端点与口令；URL 为空表示关闭同步。配置好后确保轮询线程在运行。
+pub fn set_config(app: &AppHandle, url: String, password: String) {
+    let url = url.trim().to_string();
+    if let Ok(mut slot) = config().lock() {
+        *slot = if url.is_empty() {
+            None
+        } else {
+            Some(SyncConfig {
+                url,
+                key: derive_key(&password),
+            })
+        };
+    }
+    start(app);
+}
+
+/// 把本地新捕获的文本推送到远端（在独立线程上完成，不阻塞剪贴板监听）。
+pub fn push(content: &str) {
+    let content = content.to_string();
+    std::thread::spawn(move || {
+        let Some((url, key)) = snapshot_config() else {
+            return;
+        };
+
+        let magic = format!("{}-{}", device_id(), now_ms());
+        remember(magic.clone());
+
+        let payload = SyncPayload { magic, content };
+        let Ok(json) = serde_json::to_vec(&payload) else {
+            return;
+        };
+        let body = encrypt(&json, &key);
+
+        let _ = http_client().post(&url).body(body).send();
+    });
+}
+
+/// 取出当前配置的一份拷贝（URL 与密钥），未配置时返回 `None`。
+fn snapshot_config() -> Option<(String, [u8; 32])> {
+    config()
+        .lock()
+        .ok()
+        .and_then(|slot| slot.as_ref().map(|c| (c.url.clone(), c.key)))
+}
+
+/// 启动轮询线程（幂等）。线程随进程长存：配置被清空时进入空转等待，
+/// 重新配置后继续轮询——避免「清空再设置」时退出与启动相互竞争导致轮询永久停摆。
+pub fn start(app: &AppHandle) {
+    if snapshot_config().is_none() {
+        return;
+    }
+    if POLLING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    let app = app.clone();
+    std::thread::spawn(move || loop {
+        let Some((url, key)) = snapshot_config() else {
+            std::thread::sleep(POLL_INTERVAL);
+            continue;
+        };
+
+        if let Ok(resp) = http_client().get(&url).send() {
+            if resp.status().is_success() {
+                if let Ok(body) = resp.text() {
+                    if let Some(plain) = decrypt(&body, &key) {
+                        if let Ok(payload) = serde_json::from_slice::<SyncPayload>(&plain) {
+                            apply_remote(&app, payload);
+                        }
+                    }
+                }
+            }
+        }
+
+        std::thread::sleep(POLL_INTERVAL);
+    });
+}
+
+/// 应用一条远端负载：跳过自己推送或已处理过的内容，否则入库并写回剪贴板。
+fn apply_remote(app: &AppHandle, payload: SyncPayload) {
+    if payload.magic.starts_with(device_id()) || already_seen(&payload.magic) {
+        return;
+    }
+    remember(payload.magic);
+
+    let content = payload.content;
+    if content.trim().is_empty() {
+        return;
+    }
+
+    let content_type = if crate::clipboard::is_url(&content) {
+        "link"
+    } else {
+        "text"
+    };
+    let record = ClipboardRecord {
+        id: 0,
+        content_type: content_type.to_string(),
+        content: content.clone(),
+        html: None,
+        bundle: None,
+        image_data: None,
+        is_favorite: false,
+        is_pinned: false,
+        source_app: "Sync".to_string(),
+        created_at: chrono::Local::now().to_rfc3339(),
+    };
+
+    if crate::database::add_record(record).is_err() {
+        return;
+    }
+
+    // 写回剪贴板时打标，避免监听器把远端内容当作本地复制再次记录并回推。
+    crate::clipboard::ignore_next_change();
+    let _ = crate::clipboard::set_clipboard_text(&content);
+
+    let _ = app.emit("history-changed", ());
+}
+
+/// `set_sync_config` 命令：配置加密同步的端点与口令。
+#[tauri::command]
+pub fn set_sync_config(app: AppHandle, url: String, password: String) -> Result<(), String> {
+    set_config(&app, url, password);
+    Ok(())
+}
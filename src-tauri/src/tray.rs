@@ -1,11 +1,20 @@
 use tauri::{
-    menu::{Menu, MenuItem},
+    menu::{IconMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu},
     tray::TrayIconBuilder,
     image::Image,
     AppHandle, Emitter, Manager, PhysicalPosition, Runtime,
 };
 use image::load_from_memory;
 
+/// 托盘图标 id，`refresh_tray_menu` 通过它重建菜单。
+pub const TRAY_ID: &str = "clipper-tray";
+/// 托盘「最近」子菜单里展示的剪贴板条目数量。
+const RECENT_MENU_COUNT: i32 = 8;
+/// 最近条目标签的最大显示长度。
+const RECENT_LABEL_MAX_CHARS: usize = 40;
+/// 最近条目菜单项 id 前缀，后接历史记录行 id。
+const RECENT_MENU_PREFIX: &str = "recent:";
+
 pub fn show_main_window<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<()> {
     if let Some(window) = app.get_webview_window("main") {
         crate::mark_main_window_shown();
@@ -20,50 +29,21 @@ pub fn show_main_window<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<()> {
 pub fn show_main_window_near_cursor<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<()> {
     if let Some(window) = app.get_webview_window("main") {
         crate::mark_main_window_shown();
-        if let Ok(cursor) = window.cursor_position() {
-            let mut x = cursor.x + 12.0;
-            let mut y = cursor.y + 16.0;
-
-            if let Ok(size) = window.outer_size() {
-                let work_area = window
-                    .available_monitors()
-                    .ok()
-                    .and_then(|monitors| {
-                        monitors.into_iter().find(|m| {
-                            let rect = m.work_area();
-                            let left = rect.position.x as f64;
-                            let top = rect.position.y as f64;
-                            let right = left + rect.size.width as f64;
-                            let bottom = top + rect.size.height as f64;
-                            cursor.x >= left && cursor.x < right && cursor.y >= top && cursor.y < bottom
-                        })
-                    })
-                    .or_else(|| window.current_monitor().ok().flatten())
-                    .map(|m| *m.work_area());
-
-                if let Some(rect) = work_area {
-                    let margin = 6.0;
-                    let left = rect.position.x as f64 + margin;
-                    let top = rect.position.y as f64 + margin;
-                    let right = rect.position.x as f64 + rect.size.width as f64 - margin;
-                    let bottom = rect.position.y as f64 + rect.size.height as f64 - margin;
-
-                    // 默认显示在鼠标下方，靠近底部时改为在鼠标上方显示，避免压住任务栏
-                    if y + size.height as f64 > bottom {
-                        y = cursor.y - size.height as f64 - 12.0;
-                    }
-
-                    let max_x = (right - size.width as f64).max(left);
-                    let max_y = (bottom - size.height as f64).max(top);
-                    x = x.clamp(left, max_x);
-                    y = y.clamp(top, max_y);
-                } else {
-                    x = x.max(0.0);
-                    y = y.max(0.0);
+
+        let pinned = crate::database::get_settings()
+            .map(|s| s.pinned)
+            .unwrap_or(false);
+
+        if pinned {
+            // 置顶模式下固定在用户上次停放的位置，而非每次都跟随光标。
+            apply_pinned_mode(&window);
+        } else {
+            if let Ok(cursor) = window.cursor_position() {
+                if let Some(position) = compute_near_cursor_position(&window, cursor) {
+                    let _ = window.set_position(position);
                 }
             }
-
-            let _ = window.set_position(PhysicalPosition::new(x.round() as i32, y.round() as i32));
+            apply_overlay_mode(&window);
         }
 
         // 在窗口显示之前发送事件，让前端先设置滚动位置
@@ -74,15 +54,277 @@ pub fn show_main_window_near_cursor<R: Runtime>(app: &AppHandle<R>) -> tauri::Re
     Ok(())
 }
 
-/// 创建系统托盘
-pub fn create_tray<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<()> {
-    // 创建菜单项
+/// 在弹窗显示前套用「悬浮面板」模式。
+///
+/// 当设置启用 overlay 时，窗口会置顶并从任务栏隐藏（`set_skip_taskbar`），使快捷
+/// 粘贴弹窗表现得像聚光灯面板而非普通应用窗口；本函数不改动窗口层级，也不设置
+/// 不抢焦点的窗口样式。失焦后由 `on_window_event` 的自动隐藏分支负责收起。未启用
+/// 时取消置顶并恢复任务栏显示。
+pub fn apply_overlay_mode<R: Runtime>(window: &tauri::WebviewWindow<R>) {
+    let overlay = crate::database::get_settings()
+        .map(|s| s.overlay_mode)
+        .unwrap_or(true);
+
+    let _ = window.set_always_on_top(overlay);
+    let _ = window.set_skip_taskbar(overlay);
+}
+
+/// 套用常驻置顶面板模式。
+///
+/// 与 overlay 不同，置顶模式让窗口恒置顶、重新回到任务栏（放宽 `skip_taskbar`），
+/// 并还原用户上次停放的位置与大小，使其表现为一块常驻的浮动面板；失焦自动隐藏的
+/// 短路判断由 `on_window_event` 负责。
+pub fn apply_pinned_mode<R: Runtime>(window: &tauri::WebviewWindow<R>) {
+    let _ = window.set_always_on_top(true);
+    let _ = window.set_skip_taskbar(false);
+
+    if let Ok(Some((x, y, width, height))) = crate::database::get_pinned_geometry() {
+        let _ = window.set_position(PhysicalPosition::new(x, y));
+        if width > 0 && height > 0 {
+            let _ = window.set_size(tauri::PhysicalSize::new(width as u32, height as u32));
+        }
+    }
+}
+
+/// 设置置顶状态，落库并即时作用到主窗口，是托盘、快捷键与前端命令共用的单一入口。
+///
+/// 关闭置顶前先快照当前停放几何，便于再次置顶时回到原处；开启时则还原几何并把
+/// 窗口带到前台。状态无变化时直接返回。切换后广播 `pinned-changed` 并重建托盘菜单，
+/// 使各处 UI 保持一致。
+pub fn set_pinned_mode<R: Runtime>(app: &AppHandle<R>, pinned: bool) {
+    let mut settings = crate::database::get_settings().unwrap_or_default();
+    if settings.pinned == pinned {
+        return;
+    }
+
+    if let Some(window) = app.get_webview_window("main") {
+        if pinned {
+            settings.pinned = true;
+            let _ = crate::database::save_settings(&settings);
+            apply_pinned_mode(&window);
+            let _ = window.show();
+            let _ = window.set_focus();
+        } else {
+            // 退出置顶前记下当前位置/大小，供下次置顶还原。
+            persist_pinned_geometry(&window);
+            settings.pinned = false;
+            let _ = crate::database::save_settings(&settings);
+            apply_overlay_mode(&window);
+        }
+    } else {
+        settings.pinned = pinned;
+        let _ = crate::database::save_settings(&settings);
+    }
+
+    let _ = app.emit("pinned-changed", pinned);
+    let _ = refresh_tray_menu(app);
+}
+
+/// 翻转置顶状态，返回切换后的新值。
+pub fn toggle_pinned_mode<R: Runtime>(app: &AppHandle<R>) -> bool {
+    let pinned = !crate::database::get_settings()
+        .map(|s| s.pinned)
+        .unwrap_or(false);
+    set_pinned_mode(app, pinned);
+    pinned
+}
+
+/// 记下置顶面板当前的停放位置（外框左上角）与内容尺寸，与 [`apply_pinned_mode`]
+/// 的 `set_position`/`set_size` 还原方式对应，避免反复存取造成尺寸漂移。
+fn persist_pinned_geometry<R: Runtime>(window: &tauri::WebviewWindow<R>) {
+    let Ok(position) = window.outer_position() else {
+        return;
+    };
+    let Ok(size) = window.inner_size() else {
+        return;
+    };
+    let _ = crate::database::save_pinned_geometry(
+        position.x,
+        position.y,
+        size.width as i32,
+        size.height as i32,
+    );
+}
+
+/// 在光标附近为弹窗计算落点，并在混合 DPI 多显示器下保持一致。
+///
+/// 物理像素在不同缩放的屏幕之间没有可比性，因此这里先解析光标所在显示器的
+/// `scale_factor()`，把光标、偏移量与窗口尺寸统一换算到该屏的逻辑坐标中完成
+/// 上/下翻转与工作区夹取，最后再乘回缩放系数得到物理坐标。无法解析显示器时
+/// 回退到原先的物理像素行为。
+fn compute_near_cursor_position<R: Runtime>(
+    window: &tauri::WebviewWindow<R>,
+    cursor: PhysicalPosition<f64>,
+) -> Option<PhysicalPosition<i32>> {
+    let size = window.outer_size().ok()?;
+
+    // 解析光标实际所在的显示器，退而求其次使用当前显示器。
+    let monitor = window
+        .available_monitors()
+        .ok()
+        .and_then(|monitors| {
+            monitors.into_iter().find(|m| {
+                let rect = m.work_area();
+                let left = rect.position.x as f64;
+                let top = rect.position.y as f64;
+                let right = left + rect.size.width as f64;
+                let bottom = top + rect.size.height as f64;
+                cursor.x >= left && cursor.x < right && cursor.y >= top && cursor.y < bottom
+            })
+        })
+        .or_else(|| window.current_monitor().ok().flatten());
+
+    let Some(monitor) = monitor else {
+        // 无法解析显示器时退回旧的物理像素逻辑。
+        let x = (cursor.x + 12.0).max(0.0);
+        let y = (cursor.y + 16.0).max(0.0);
+        return Some(PhysicalPosition::new(x.round() as i32, y.round() as i32));
+    };
+
+    let scale = monitor.scale_factor();
+    let rect = monitor.work_area();
+
+    // 统一换算到该屏的逻辑坐标。
+    let cursor_x = cursor.x / scale;
+    let cursor_y = cursor.y / scale;
+    let win_w = size.width as f64 / scale;
+    let win_h = size.height as f64 / scale;
+
+    let margin = 6.0;
+    let left = rect.position.x as f64 / scale + margin;
+    let top = rect.position.y as f64 / scale + margin;
+    let right = (rect.position.x as f64 + rect.size.width as f64) / scale - margin;
+    let bottom = (rect.position.y as f64 + rect.size.height as f64) / scale - margin;
+
+    let mut x = cursor_x + 12.0;
+    let mut y = cursor_y + 16.0;
+
+    // 默认显示在鼠标下方，靠近底部时改为在鼠标上方显示，避免压住任务栏
+    if y + win_h > bottom {
+        y = cursor_y - win_h - 12.0;
+    }
+
+    let max_x = (right - win_w).max(left);
+    let max_y = (bottom - win_h).max(top);
+    x = x.clamp(left, max_x);
+    y = y.clamp(top, max_y);
+
+    // 换算回目标屏的物理坐标后再定位。
+    Some(PhysicalPosition::new(
+        (x * scale).round() as i32,
+        (y * scale).round() as i32,
+    ))
+}
+
+/// 把单行文本裁剪为适合菜单项显示的标签。
+fn truncate_label(text: &str) -> String {
+    let single_line = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if single_line.chars().count() > RECENT_LABEL_MAX_CHARS {
+        let truncated: String = single_line.chars().take(RECENT_LABEL_MAX_CHARS).collect();
+        format!("{}…", truncated)
+    } else {
+        single_line
+    }
+}
+
+/// 为图片条目生成一个小缩略图图标，解码失败时返回 `None`。
+fn thumbnail_icon(image_data: &[u8]) -> Option<Image<'static>> {
+    let decoded = load_from_memory(image_data).ok()?;
+    let thumb = decoded.thumbnail(16, 16).to_rgba8();
+    let (width, height) = thumb.dimensions();
+    Some(Image::new_owned(thumb.into_raw(), width, height))
+}
+
+/// 构建「最近」子菜单，列出最近若干条剪贴板记录供一键粘贴。
+fn build_recent_submenu<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<Submenu<R>> {
+    let submenu = Submenu::with_id(app, "recent", "最近", true)?;
+
+    let records = crate::database::get_history(RECENT_MENU_COUNT, 0).unwrap_or_default();
+    if records.is_empty() {
+        let empty = MenuItem::with_id(app, "recent:empty", "暂无记录", false, None::<&str>)?;
+        submenu.append(&empty)?;
+        return Ok(submenu);
+    }
+
+    for record in records {
+        let item_id = format!("{}{}", RECENT_MENU_PREFIX, record.id);
+        if record.content_type == "image" {
+            let icon = crate::database::get_record_by_id(record.id)
+                .ok()
+                .flatten()
+                .and_then(|full| full.image_data)
+                .and_then(|bytes| thumbnail_icon(&bytes));
+            let label = truncate_label(&record.content);
+            let item = IconMenuItem::with_id(app, &item_id, label, true, icon, None::<&str>)?;
+            submenu.append(&item)?;
+        } else {
+            let item = MenuItem::with_id(
+                app,
+                &item_id,
+                truncate_label(&record.content),
+                true,
+                None::<&str>,
+            )?;
+            submenu.append(&item)?;
+        }
+    }
+
+    Ok(submenu)
+}
+
+/// 组装完整的托盘菜单（最近记录 + 固定项）。
+fn build_tray_menu<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<Menu<R>> {
+    let recent = build_recent_submenu(app)?;
+    let separator = PredefinedMenuItem::separator(app)?;
+    let pinned = crate::database::get_settings()
+        .map(|s| s.pinned)
+        .unwrap_or(false);
+    let pin_label = if pinned { "取消置顶" } else { "置顶窗口" };
+    let pin = MenuItem::with_id(app, "toggle_pin", pin_label, true, None::<&str>)?;
     let settings = MenuItem::with_id(app, "settings", "设置", true, None::<&str>)?;
     let about = MenuItem::with_id(app, "about", "关于", true, None::<&str>)?;
     let quit = MenuItem::with_id(app, "quit", "退出程序", true, None::<&str>)?;
 
-    // 创建菜单
-    let menu = Menu::with_items(app, &[&settings, &about, &quit])?;
+    Menu::with_items(app, &[&recent, &separator, &pin, &settings, &about, &quit])
+}
+
+/// 重建托盘菜单以反映最新的剪贴板记录。
+///
+/// 在托盘被打开或捕获到新条目后调用，让「最近」子菜单与历史保持同步。
+pub fn refresh_tray_menu<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<()> {
+    if let Some(tray) = app.tray_by_id(TRAY_ID) {
+        let menu = build_tray_menu(app)?;
+        tray.set_menu(Some(menu))?;
+    }
+    Ok(())
+}
+
+/// 处理「最近」子菜单项：把选中的记录写回剪贴板并触发粘贴。
+fn handle_recent_menu_event<R: Runtime>(app: &AppHandle<R>, raw_id: &str) {
+    let Some(id_str) = raw_id.strip_prefix(RECENT_MENU_PREFIX) else {
+        return;
+    };
+    let Ok(id) = id_str.parse::<i64>() else {
+        return;
+    };
+
+    let Ok(Some(record)) = crate::database::get_record_by_id(id) else {
+        return;
+    };
+
+    // 图片记录暂不从托盘直接粘贴，仅处理文本类内容写回剪贴板。
+    if record.content_type != "image" {
+        let _ = crate::clipboard::set_clipboard_text(&record.content);
+    }
+
+    // 通知前端/粘贴流程：用户选择了某条记录，触发粘贴。
+    let _ = app.emit("tray-paste-entry", id);
+}
+
+/// 创建系统托盘
+pub fn create_tray<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<()> {
+    // 创建菜单（含「最近」子菜单）
+    let menu = build_tray_menu(app)?;
 
     // 托盘图标优先使用应用默认图标；若缺失则从文件解码。
     let icon = if let Some(default_icon) = app.default_window_icon() {
@@ -100,12 +342,13 @@ pub fn create_tray<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<()> {
     };
 
     // 创建托盘
-    let _tray = TrayIconBuilder::new()
+    let _tray = TrayIconBuilder::with_id(TRAY_ID)
         .icon(icon)
         .tooltip("Clipper - 历史记录")
         .menu(&menu)
         .on_menu_event(move |app, event| {
-            match event.id.as_ref() {
+            let id = event.id.as_ref();
+            match id {
                 "settings" => {
                     let _ = show_main_window(app);
                     let _ = app.emit("open-settings", ());
@@ -114,20 +357,33 @@ pub fn create_tray<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<()> {
                     let _ = show_main_window(app);
                     let _ = app.emit("open-about", ());
                 }
+                "toggle_pin" => {
+                    toggle_pinned_mode(app);
+                }
                 "quit" => {
                     app.exit(0);
                 }
+                other if other.starts_with(RECENT_MENU_PREFIX) => {
+                    handle_recent_menu_event(app, other);
+                }
                 _ => {}
             }
         })
         .on_tray_icon_event(|tray, event| {
-            // 左键点击托盘图标显示窗口
-            if let tauri::tray::TrayIconEvent::Click {
-                button: tauri::tray::MouseButton::Left,
-                ..
-            } = event {
-                let app_handle = tray.app_handle();
-                let _ = show_main_window(app_handle);
+            let app_handle = tray.app_handle();
+            match event {
+                // 左键点击托盘图标显示窗口
+                tauri::tray::TrayIconEvent::Click {
+                    button: tauri::tray::MouseButton::Left,
+                    ..
+                } => {
+                    let _ = show_main_window(app_handle);
+                }
+                // 菜单将要展开前刷新「最近」列表，保证展示的是最新记录。
+                tauri::tray::TrayIconEvent::Enter { .. } => {
+                    let _ = refresh_tray_menu(app_handle);
+                }
+                _ => {}
             }
         })
         .build(app)?;